@@ -1,7 +1,7 @@
 #!/bin/bash
 set -e
 
-RUSTFLAGS='-C link-arg=-s' cargo build --all --target wasm32-unknown-unknown --release
+RUSTFLAGS='-C link-arg=-s' cargo build -p coin --target wasm32-unknown-unknown --release
 mkdir -p ../out
 
 cp ./target/wasm32-unknown-unknown/release/*.wasm ../out/
\ No newline at end of file
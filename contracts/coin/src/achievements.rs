@@ -0,0 +1,312 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
+use near_contract_standards::non_fungible_token::TokenId;
+
+// Badge tokens share NFT collection with box NFTs, but can't be transferred or opened
+pub const BADGE_TOKEN_PREFIX: &str = "badge:";
+// Jackpot reward type index
+pub const JACKPOT_REWARD_TYPE: usize = 4;
+pub const DAILY_STREAK_ACHIEVEMENT: u32 = 7;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    FirstBox,
+    Boxes100,
+    Boxes1000,
+    PremiumWin,
+    Jackpot,
+    NearLeaderboardTop,
+    LolLeaderboardTop,
+    DailyStreak7,
+}
+
+impl Achievement {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Achievement::FirstBox => "first_box",
+            Achievement::Boxes100 => "boxes_100",
+            Achievement::Boxes1000 => "boxes_1000",
+            Achievement::PremiumWin => "premium_win",
+            Achievement::Jackpot => "jackpot",
+            Achievement::NearLeaderboardTop => "near_leaderboard_top",
+            Achievement::LolLeaderboardTop => "lol_leaderboard_top",
+            Achievement::DailyStreak7 => "daily_streak_7",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::FirstBox => "First Box",
+            Achievement::Boxes100 => "Box Collector",
+            Achievement::Boxes1000 => "Box Maniac",
+            Achievement::PremiumWin => "Premium Winner",
+            Achievement::Jackpot => "Jackpot",
+            Achievement::NearLeaderboardTop => "NEAR Leader",
+            Achievement::LolLeaderboardTop => "LOL Leader",
+            Achievement::DailyStreak7 => "Weekly Regular",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstBox => "Opened first LOL box",
+            Achievement::Boxes100 => "Opened 100 LOL boxes",
+            Achievement::Boxes1000 => "Opened 1000 LOL boxes",
+            Achievement::PremiumWin => "Won NEAR from premium box",
+            Achievement::Jackpot => "Won 1000 NEAR prize or progressive jackpot",
+            Achievement::NearLeaderboardTop => "Reached first place in NEAR leaderboard",
+            Achievement::LolLeaderboardTop => "Reached first place in LOL leaderboard",
+            Achievement::DailyStreak7 => "Claimed daily box 7 days in a row",
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UnlockedAchievement {
+    pub achievement: Achievement,
+    pub token_id: TokenId,
+    pub unlocked_at: Timestamp,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_achievements(&self, account_id: AccountId) -> Vec<UnlockedAchievement> {
+        self.user_achievements.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn has_achievement(&self, account_id: AccountId, achievement: Achievement) -> bool {
+        self.get_achievements(account_id).iter().any(|item| item.achievement == achievement)
+    }
+}
+
+impl Contract {
+    // Unlock achievements reached by user after box is opened, reward_type_index is 0 for basic box.
+    // Called only by box opening that charges storage, achievements reached with free boxes
+    // (daily streak, box counters, leaderboards) are unlocked with the next paid box
+    pub(crate) fn _check_achievements(&mut self, owner_id: &AccountId, reward_type_index: usize, jackpot_won: bool) {
+        let user = self._get_user(owner_id);
+        let mut reached: Vec<Achievement> = vec![];
+
+        if user.total_box_opened >= 1 {
+            reached.push(Achievement::FirstBox);
+        }
+        if user.total_box_opened >= 100 {
+            reached.push(Achievement::Boxes100);
+        }
+        if user.total_box_opened >= 1000 {
+            reached.push(Achievement::Boxes1000);
+        }
+        if reward_type_index != 0 {
+            reached.push(Achievement::PremiumWin);
+        }
+        if reward_type_index == JACKPOT_REWARD_TYPE || jackpot_won {
+            reached.push(Achievement::Jackpot);
+        }
+        if self.near_leaderboard.first().map(|item| &item.account_id) == Some(owner_id) {
+            reached.push(Achievement::NearLeaderboardTop);
+        }
+        if self.lol_leaderboard.first().map(|item| &item.account_id) == Some(owner_id) {
+            reached.push(Achievement::LolLeaderboardTop);
+        }
+        if user.daily_streak >= DAILY_STREAK_ACHIEVEMENT {
+            reached.push(Achievement::DailyStreak7);
+        }
+
+        let mut unlocked = self.user_achievements.get(owner_id).unwrap_or_default();
+        let unlocked_count = unlocked.len();
+        for achievement in reached {
+            if unlocked.iter().any(|item| item.achievement == achievement) {
+                continue;
+            }
+            let token_id = self._mint_badge(owner_id, achievement);
+            log!("Achievement: {}, {}", owner_id, achievement.code());
+            unlocked.push(UnlockedAchievement {
+                achievement,
+                token_id,
+                unlocked_at: env::block_timestamp(),
+            });
+        }
+
+        if unlocked.len() > unlocked_count {
+            self.user_achievements.insert(owner_id, &unlocked);
+        }
+    }
+
+    fn _mint_badge(&mut self, owner_id: &AccountId, achievement: Achievement) -> TokenId {
+        let token_id = format!("{}{}:{}", BADGE_TOKEN_PREFIX, achievement.code(), owner_id);
+        let metadata = TokenMetadata {
+            title: Some(achievement.title().to_string()),
+            description: Some(achievement.description().to_string()),
+            media: None,
+            media_hash: None,
+            copies: Some(1),
+            issued_at: Some(env::block_timestamp().to_string()),
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: Some(format!("{{\"achievement\":\"{}\",\"soulbound\":true}}", achievement.code())),
+            reference: None,
+            reference_hash: None,
+        };
+        // Storage is charged by the calling method from paid box price
+        self.nft.internal_mint_with_refund(token_id.clone(), owner_id.clone(), Some(metadata), None);
+        near_contract_standards::non_fungible_token::events::NftMint {
+            owner_id,
+            token_ids: &[&token_id],
+            memo: Some(achievement.code()),
+        }.emit();
+
+        token_id
+    }
+
+    pub(crate) fn _assert_transferable(&self, token_id: &TokenId) {
+        if token_id.starts_with(BADGE_TOKEN_PREFIX) {
+            ContractError::SoulboundToken.panic();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenCore;
+    use near_contract_standards::non_fungible_token::enumeration::NonFungibleTokenEnumeration;
+    use near_sdk::testing_env;
+
+    fn achievements(contract: &Contract, account_id: &AccountId) -> Vec<&'static str> {
+        contract.get_achievements(account_id.clone()).iter().map(|item| item.achievement.code()).collect()
+    }
+
+    // User state reached before the checked box
+    fn setup_user(contract: &mut Contract, account_id: &AccountId, total_box_opened: u32, daily_streak: u32) {
+        testing_env!(context(account_id).build());
+        let mut user = contract._get_user(account_id);
+        user.total_box_opened = total_box_opened;
+        user.daily_streak = daily_streak;
+        contract._save_user(account_id, &user);
+    }
+
+    #[test]
+    fn first_box_mints_soulbound_badge() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        testing_env!(context(&alice).attached_deposit(OPEN_BOX_PRICE).build());
+
+        contract.open_box(None);
+
+        assert!(contract.has_achievement(alice.clone(), Achievement::FirstBox));
+        let token = contract.nft_token("badge:first_box:alice.near".to_string()).unwrap();
+        assert_eq!(token.owner_id, alice);
+        let metadata = token.metadata.unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("First Box"));
+        assert_eq!(metadata.extra.as_deref(), Some(r#"{"achievement":"first_box","soulbound":true}"#));
+    }
+
+    #[test]
+    fn box_counter_tiers_are_unlocked() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        setup_user(&mut contract, &alice, 99, 0);
+        contract._check_achievements(&alice, 0, false);
+        assert_eq!(achievements(&contract, &alice), vec!["first_box"]);
+
+        setup_user(&mut contract, &alice, 100, 0);
+        contract._check_achievements(&alice, 0, false);
+        assert_eq!(achievements(&contract, &alice), vec!["first_box", "boxes_100"]);
+
+        setup_user(&mut contract, &alice, 999, 0);
+        open_box(&mut contract, &alice, None);
+        assert!(contract.has_achievement(alice, Achievement::Boxes1000));
+    }
+
+    #[test]
+    fn premium_and_jackpot_rewards_are_unlocked() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let bob = account("bob.near");
+        setup_user(&mut contract, &alice, 1, 0);
+        setup_user(&mut contract, &bob, 1, 0);
+
+        contract._check_achievements(&alice, JACKPOT_REWARD_TYPE, false);
+        contract._check_achievements(&bob, 0, true);
+
+        assert_eq!(achievements(&contract, &alice), vec!["first_box", "premium_win", "jackpot"]);
+        assert_eq!(achievements(&contract, &bob), vec!["first_box", "jackpot"]);
+    }
+
+    #[test]
+    fn premium_reward_below_tier_4_is_not_jackpot() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        setup_user(&mut contract, &alice, 1, 0);
+
+        contract._check_achievements(&alice, JACKPOT_REWARD_TYPE - 1, false);
+
+        assert_eq!(achievements(&contract, &alice), vec!["first_box", "premium_win"]);
+    }
+
+    #[test]
+    fn leaderboard_leaders_are_unlocked() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let bob = account("bob.near");
+        contract.near_leaderboard = vec![
+            LeaderboardItem { account_id: alice.clone(), amount: U128(2 * ONE_TOKEN) },
+            LeaderboardItem { account_id: bob.clone(), amount: U128(ONE_TOKEN) },
+        ];
+        contract.lol_leaderboard = vec![LeaderboardItem { account_id: bob.clone(), amount: U128(ONE_TOKEN) }];
+        setup_user(&mut contract, &alice, 1, 0);
+        setup_user(&mut contract, &bob, 1, 0);
+
+        contract._check_achievements(&alice, 0, false);
+        contract._check_achievements(&bob, 0, false);
+
+        assert_eq!(achievements(&contract, &alice), vec!["first_box", "near_leaderboard_top"]);
+        assert_eq!(achievements(&contract, &bob), vec!["first_box", "lol_leaderboard_top"]);
+    }
+
+    #[test]
+    fn daily_streak_is_unlocked() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        setup_user(&mut contract, &alice, 1, DAILY_STREAK_ACHIEVEMENT - 1);
+        contract._check_achievements(&alice, 0, false);
+        assert!(!contract.has_achievement(alice.clone(), Achievement::DailyStreak7));
+
+        setup_user(&mut contract, &alice, 1, DAILY_STREAK_ACHIEVEMENT);
+        contract._check_achievements(&alice, 0, false);
+        assert!(contract.has_achievement(alice, Achievement::DailyStreak7));
+    }
+
+    #[test]
+    fn achievement_is_unlocked_once() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        setup_user(&mut contract, &alice, 1, 0);
+        contract._check_achievements(&alice, JACKPOT_REWARD_TYPE, true);
+        let unlocked = contract.get_achievements(alice.clone());
+
+        contract._check_achievements(&alice, JACKPOT_REWARD_TYPE, true);
+
+        let repeated = contract.get_achievements(alice.clone());
+        assert_eq!(repeated.len(), unlocked.len());
+        assert_eq!(repeated[0].token_id, unlocked[0].token_id);
+        assert_eq!(contract.nft_supply_for_owner(alice).0, unlocked.len() as u128);
+    }
+
+    #[test]
+    #[should_panic(expected = "[SOULBOUND_TOKEN]")]
+    fn badge_is_not_transferable() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        setup_user(&mut contract, &alice, 1, 0);
+        contract._check_achievements(&alice, 0, false);
+
+        testing_env!(context(&alice).attached_deposit(1).build());
+        contract.nft_transfer(account("bob.near"), "badge:first_box:alice.near".to_string(), None, None);
+    }
+}
@@ -0,0 +1,388 @@
+use crate::*;
+use near_contract_standards::non_fungible_token::approval::NonFungibleTokenApproval;
+use near_contract_standards::non_fungible_token::core::{NonFungibleTokenCore, NonFungibleTokenResolver};
+use near_contract_standards::non_fungible_token::events::NftBurn;
+use near_contract_standards::non_fungible_token::metadata::{
+    NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata, NFT_METADATA_SPEC,
+};
+use near_contract_standards::non_fungible_token::{Token, TokenId};
+
+pub const BOX_SEASON: u32 = 1;
+
+#[near_bindgen]
+impl Contract {
+    // Buy boxes as NFTs, they can be traded and opened later by current owner with open_box_nft.
+    // Phase allowlist and account limit are checked for buyer before minting.
+    // Attached deposit covers box price and NFT storage, the rest is collected as box payment
+    #[payable]
+    pub fn buy_box_nfts(&mut self, count: u32) -> Vec<TokenId> {
+        let owner_id = env::predecessor_account_id();
+        let price = self._box_price(&owner_id);
+        if count == 0 || count > MAX_BOXES_PER_CALL {
+            ContractError::WrongBoxesCount.panic();
+        }
+        self._assert_can_reserve_boxes(&owner_id, count);
+
+        let box_type = match self._current_phase() {
+            Phase::Allowlist => "allowlist",
+            _ => "public",
+        };
+        let initial_storage_usage = env::storage_usage();
+
+        let mut user = self._get_user(&owner_id);
+        user.box_nfts_bought += count;
+        self._save_user(&owner_id, &user);
+
+        let mut token_ids: Vec<TokenId> = vec![];
+        for _ in 0..count {
+            self.box_nft_next_id += 1;
+            let token_id = self.box_nft_next_id.to_string();
+            let metadata = TokenMetadata {
+                title: Some(format!("LOL Box #{}", token_id)),
+                description: Some(format!("Unopened LOL Memecoin box, season {}", BOX_SEASON)),
+                media: None,
+                media_hash: None,
+                copies: None,
+                issued_at: Some(env::block_timestamp().to_string()),
+                expires_at: None,
+                starts_at: None,
+                updated_at: None,
+                extra: Some(format!("{{\"season\":{},\"box_type\":\"{}\"}}", BOX_SEASON, box_type)),
+                reference: None,
+                reference_hash: None,
+            };
+            self.nft.internal_mint_with_refund(token_id.clone(), owner_id.clone(), Some(metadata), None);
+            self.box_nft_price.insert(&token_id, &price);
+            token_ids.push(token_id);
+        }
+        near_contract_standards::non_fungible_token::events::NftMint {
+            owner_id: &owner_id,
+            token_ids: &token_ids.iter().map(|token_id| token_id.as_str()).collect::<Vec<&str>>(),
+            memo: None,
+        }.emit();
+
+        let escrow = price * count as u128;
+        let storage_fee = self._record_storage_usage(&owner_id, initial_storage_usage);
+        if env::attached_deposit() < escrow + storage_fee {
+            ContractError::WrongDeposit.panic();
+        }
+        self.total_box_reserved += count;
+        self.treasury.box_escrow += escrow;
+        self.treasury.storage_fees += storage_fee;
+        self._collect_box_payment(env::attached_deposit() - escrow - storage_fee);
+
+        token_ids
+    }
+
+    // Burn box NFT and open box for its current owner
+    pub fn open_box_nft(&mut self, token_id: TokenId) -> (usize, U128, U128) {
+        let owner_id = env::predecessor_account_id();
+        self._assert_can_open_reserved_box(&owner_id);
+        let price = self._burn_box_nft(&owner_id, &token_id);
+
+        self.total_box_reserved -= 1;
+        self.treasury.box_escrow -= price;
+
+        let initial_storage_usage = env::storage_usage();
+        let result = self._open_box(owner_id.clone(), BoxPayment::Near(price));
+
+        // Storage fee is deducted from box NFT price
+        let storage_fee = self._record_storage_usage(&owner_id, initial_storage_usage).min(price);
+        self.treasury.storage_fees += storage_fee;
+        self._collect_box_payment(price - storage_fee);
+
+        result
+    }
+
+    // Box NFT that was not opened before campaign finalization is burned and paid back to its owner
+    pub fn refund_box_nft(&mut self, token_id: TokenId) -> U128 {
+        if !self.campaign_finalized {
+            ContractError::CampaignNotOver.panic();
+        }
+
+        let owner_id = env::predecessor_account_id();
+        let price = self._burn_box_nft(&owner_id, &token_id);
+        self.total_box_reserved -= 1;
+        self.treasury.box_escrow -= price;
+
+        log!("Box NFT refunded: {}, {}, {}", owner_id, token_id, price);
+        Promise::new(owner_id).transfer(price);
+
+        price.into()
+    }
+}
+
+impl Contract {
+    // Remove token from all NFT collections, returns price paid for the box
+    fn _burn_box_nft(&mut self, owner_id: &AccountId, token_id: &TokenId) -> Balance {
+        self._assert_transferable(token_id);
+        match self.nft.owner_by_id.get(token_id) {
+            Some(token_owner_id) if &token_owner_id == owner_id => {}
+            Some(_) => ContractError::NotTokenOwner.panic(),
+            None => ContractError::TokenNotFound.panic(),
+        }
+
+        self.nft.owner_by_id.remove(token_id);
+        if let Some(token_metadata_by_id) = &mut self.nft.token_metadata_by_id {
+            token_metadata_by_id.remove(token_id);
+        }
+        if let Some(tokens_per_owner) = &mut self.nft.tokens_per_owner {
+            if let Some(mut token_ids) = tokens_per_owner.get(owner_id) {
+                token_ids.remove(token_id);
+                if token_ids.is_empty() {
+                    tokens_per_owner.remove(owner_id);
+                } else {
+                    tokens_per_owner.insert(owner_id, &token_ids);
+                }
+            }
+        }
+        if let Some(approvals_by_id) = &mut self.nft.approvals_by_id {
+            approvals_by_id.remove(token_id);
+        }
+        if let Some(next_approval_id_by_id) = &mut self.nft.next_approval_id_by_id {
+            next_approval_id_by_id.remove(token_id);
+        }
+
+        NftBurn {
+            owner_id,
+            token_ids: &[token_id],
+            authorized_id: None,
+            memo: None,
+        }.emit();
+
+        self.box_nft_price.remove(token_id).unwrap_or(0)
+    }
+}
+
+// Same as impl_non_fungible_token_core and impl_non_fungible_token_approval, but achievement badges are soulbound
+#[near_bindgen]
+impl NonFungibleTokenCore for Contract {
+    #[payable]
+    fn nft_transfer(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        self._assert_transferable(&token_id);
+        self.nft.nft_transfer(receiver_id, token_id, approval_id, memo)
+    }
+
+    #[payable]
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        self._assert_transferable(&token_id);
+        self.nft.nft_transfer_call(receiver_id, token_id, approval_id, memo, msg)
+    }
+
+    fn nft_token(&self, token_id: TokenId) -> Option<Token> {
+        self.nft.nft_token(token_id)
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenResolver for Contract {
+    #[private]
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<std::collections::HashMap<AccountId, u64>>,
+    ) -> bool {
+        self.nft.nft_resolve_transfer(previous_owner_id, receiver_id, token_id, approved_account_ids)
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenApproval for Contract {
+    #[payable]
+    fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: AccountId,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        self._assert_transferable(&token_id);
+        self.nft.nft_approve(token_id, account_id, msg)
+    }
+
+    #[payable]
+    fn nft_revoke(&mut self, token_id: TokenId, account_id: AccountId) {
+        self.nft.nft_revoke(token_id, account_id)
+    }
+
+    #[payable]
+    fn nft_revoke_all(&mut self, token_id: TokenId) {
+        self.nft.nft_revoke_all(token_id)
+    }
+
+    fn nft_is_approved(
+        &self,
+        token_id: TokenId,
+        approved_account_id: AccountId,
+        approval_id: Option<u64>,
+    ) -> bool {
+        self.nft.nft_is_approved(token_id, approved_account_id, approval_id)
+    }
+}
+
+near_contract_standards::impl_non_fungible_token_enumeration!(Contract, nft);
+
+#[near_bindgen]
+impl NonFungibleTokenMetadataProvider for Contract {
+    fn nft_metadata(&self) -> NFTContractMetadata {
+        NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: "LOL Memecoin Boxes".to_string(),
+            symbol: "LOLBOX".to_string(),
+            icon: Some(IMAGE_ICON.to_string()),
+            base_uri: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+    use near_sdk::test_utils::get_created_receipts;
+    use near_sdk::mock::VmAction;
+
+    // Deposit over boxes price that covers NFT storage
+    const STORAGE_DEPOSIT: Balance = ONE_TOKEN / 10;
+
+    fn buy(contract: &mut Contract, account_id: &AccountId, count: u32) -> Vec<TokenId> {
+        testing_env!(context(account_id).attached_deposit(OPEN_BOX_PRICE * count as u128 + STORAGE_DEPOSIT).build());
+        contract.buy_box_nfts(count)
+    }
+
+    fn transfer(contract: &mut Contract, sender_id: &AccountId, receiver_id: &AccountId, token_id: &TokenId, approval_id: Option<u64>) {
+        testing_env!(context(sender_id).attached_deposit(1).build());
+        contract.nft_transfer(receiver_id.clone(), token_id.clone(), approval_id, None);
+    }
+
+    #[test]
+    fn buy_box_nfts_reserves_boxes() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let total_box_remain = contract.total_box_remain;
+
+        let token_ids = buy(&mut contract, &alice, 2);
+
+        assert_eq!(token_ids, vec!["1".to_string(), "2".to_string()]);
+        assert_eq!(contract.nft_supply_for_owner(alice.clone()).0, 2);
+        assert_eq!(contract.total_box_reserved, 2);
+        assert_eq!(contract.total_box_remain, total_box_remain);
+
+        // NFT storage is metered for buyer, the rest of deposit over price is box payment
+        let storage_used = contract._get_user(&alice).storage_used;
+        assert!(storage_used > 0);
+        let storage_fee = storage_used as u128 * env::storage_byte_cost();
+        let treasury = contract.get_treasury();
+        assert_eq!(treasury.box_escrow.0, 2 * OPEN_BOX_PRICE);
+        assert_eq!(treasury.storage_fees.0, storage_fee);
+        assert_eq!(treasury.total_collected.0, STORAGE_DEPOSIT - storage_fee);
+        assert!(get_created_receipts().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "[WRONG_DEPOSIT]")]
+    fn buy_box_nfts_without_storage_deposit() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        testing_env!(context(&alice).attached_deposit(OPEN_BOX_PRICE).build());
+
+        contract.buy_box_nfts(1);
+    }
+
+    #[test]
+    fn transferred_box_nft_is_opened_by_new_owner() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let bob = account("bob.near");
+        let token_id = buy(&mut contract, &alice, 1).remove(0);
+
+        transfer(&mut contract, &alice, &bob, &token_id, None);
+        testing_env!(context(&bob).build());
+        contract.open_box_nft(token_id.clone());
+
+        assert!(contract.nft_token(token_id).is_none());
+        assert_eq!(contract.get_user_rewards(bob).2, 1);
+        assert_eq!(contract.get_user_rewards(alice).2, 0);
+        assert_eq!(contract.total_box_reserved, 0);
+        assert_eq!(contract.get_treasury().box_escrow.0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOT_TOKEN_OWNER]")]
+    fn transferred_box_nft_is_not_opened_by_previous_owner() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let token_id = buy(&mut contract, &alice, 1).remove(0);
+        transfer(&mut contract, &alice, &account("bob.near"), &token_id, None);
+
+        testing_env!(context(&alice).build());
+        contract.open_box_nft(token_id);
+    }
+
+    #[test]
+    fn approved_account_transfers_box_nft() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let bob = account("bob.near");
+        let carol = account("carol.near");
+        let token_id = buy(&mut contract, &alice, 1).remove(0);
+
+        testing_env!(context(&alice).attached_deposit(STORAGE_DEPOSIT).build());
+        contract.nft_approve(token_id.clone(), bob.clone(), None);
+        assert!(contract.nft_is_approved(token_id.clone(), bob.clone(), None));
+
+        transfer(&mut contract, &bob, &carol, &token_id, Some(1));
+        assert!(!contract.nft_is_approved(token_id.clone(), bob, None));
+
+        testing_env!(context(&carol).build());
+        contract.open_box_nft(token_id);
+        assert_eq!(contract.get_user_rewards(carol).2, 1);
+    }
+
+    #[test]
+    fn box_nft_is_refunded_after_finalization() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let token_id = buy(&mut contract, &alice, 1).remove(0);
+        contract.campaign_finalized = true;
+
+        testing_env!(context(&alice).build());
+        assert_eq!(contract.refund_box_nft(token_id.clone()).0, OPEN_BOX_PRICE);
+
+        assert!(contract.nft_token(token_id).is_none());
+        assert_eq!(contract.total_box_reserved, 0);
+        assert_eq!(contract.get_treasury().box_escrow.0, 0);
+        let refund = get_created_receipts().into_iter().find(|receipt| receipt.receiver_id == alice);
+        assert!(matches!(
+            refund.unwrap().actions[..],
+            [VmAction::Transfer { deposit }] if deposit == OPEN_BOX_PRICE
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "[CAMPAIGN_NOT_OVER]")]
+    fn box_nft_is_not_refunded_before_finalization() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let token_id = buy(&mut contract, &alice, 1).remove(0);
+
+        contract.refund_box_nft(token_id);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::*;
+use near_contract_standards::fungible_token::events::FtBurn;
+use near_sdk::assert_one_yocto;
+
+#[near_bindgen]
+impl Contract {
+    // Burn LOL tokens from caller balance, reduces total supply
+    #[payable]
+    pub fn burn(&mut self, amount: U128) {
+        assert_one_yocto();
+        if amount.0 == 0 {
+            ContractError::WrongAmount.panic();
+        }
+
+        let account_id = env::predecessor_account_id();
+        self._internal_burn(&account_id, amount.into(), "Tokens burned");
+        self._add_user_burned(&account_id, amount.into());
+    }
+
+    pub fn get_burned_total(&self) -> U128 {
+        self.burned_total.into()
+    }
+
+    pub fn get_user_burned(&self, account_id: AccountId) -> U128 {
+        self.user_burned.get(&account_id).unwrap_or(0).into()
+    }
+
+    pub fn get_burn_leaderboard(&self) -> &Vec<LeaderboardItem> {
+        &self.burn_leaderboard
+    }
+}
+
+impl Contract {
+    // Withdraw tokens from account and reduce total supply
+    pub(crate) fn _internal_burn(&mut self, account_id: &AccountId, amount: Balance, memo: &str) {
+        self.token.internal_withdraw(account_id, amount);
+        self.burned_total += amount;
+
+        FtBurn {
+            owner_id: account_id,
+            amount: &amount.into(),
+            memo: Some(memo),
+        }.emit();
+    }
+
+    pub(crate) fn _add_user_burned(&mut self, account_id: &AccountId, amount: Balance) {
+        let user_burned = self.user_burned.get(account_id).unwrap_or(0) + amount;
+        self.user_burned.insert(account_id, &user_burned);
+
+        // Update leaderboard
+        self._update_leaderboard("burn_leaderboard", account_id, user_burned);
+    }
+}
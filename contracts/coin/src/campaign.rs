@@ -0,0 +1,176 @@
+use crate::*;
+use crate::events::{CampaignFinalizedData, LolEvent};
+
+#[near_bindgen]
+impl Contract {
+    pub fn is_campaign_finalized(&self) -> bool {
+        self.campaign_finalized
+    }
+
+    // -------------- Admin functions --------------
+
+    // Move unclaimed LOL box allocation and daily boxes pool to configured destination when campaign is over
+    pub fn finalize_campaign(&mut self) -> U128 {
+        self._assert_owner();
+        if self.campaign_finalized {
+            ContractError::CampaignFinalized.panic();
+        }
+
+        // Reserved boxes are not opened yet and still need LOL rewards, so they don't make campaign sold out
+        let is_sold_out = self.total_box_remain == 0;
+        let is_deadline_passed = self.config.campaign_end_timestamp
+            .map(|campaign_end_timestamp| env::block_timestamp() >= campaign_end_timestamp)
+            .unwrap_or(false);
+        if !is_sold_out && !is_deadline_passed {
+            ContractError::CampaignNotOver.panic();
+        }
+
+        let lol_amount = self.lol_tokens_remain;
+        let daily_pool_amount = self.daily_lol_pool;
+        let amount = lol_amount + daily_pool_amount;
+        self.lol_tokens_remain = 0;
+        self.daily_lol_pool = 0;
+        self.campaign_finalized = true;
+        self._release_jackpot();
+
+        if amount > 0 {
+            let current_contract = env::current_account_id();
+            match self.config.unclaimed_destination.clone() {
+                UnclaimedDestination::Burn => {
+                    self._internal_burn(&current_contract, amount, "Unclaimed box rewards are burned");
+                }
+                UnclaimedDestination::Liquidity => {
+                    let lp_contract = self.config.liquidity_account_id();
+                    self.token.internal_transfer(&current_contract, &lp_contract, amount, Some("Unclaimed box rewards".to_string()));
+                }
+                UnclaimedDestination::Treasury(treasury_id) => {
+                    if !self.token.accounts.contains_key(&treasury_id) {
+                        self.token.internal_register_account(&treasury_id);
+                    }
+                    self.token.internal_transfer(&current_contract, &treasury_id, amount, Some("Unclaimed box rewards".to_string()));
+                }
+            }
+        }
+
+        LolEvent::CampaignFinalized(vec![CampaignFinalizedData {
+            destination: &self.config.unclaimed_destination,
+            lol_amount: lol_amount.into(),
+            daily_pool_amount: daily_pool_amount.into(),
+        }])
+        .emit();
+        amount.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+    use near_sdk::test_utils::get_logs;
+
+    fn setup_sold_out(destination: UnclaimedDestination) -> Contract {
+        let mut contract = setup_contract();
+        contract.config.unclaimed_destination = destination;
+        contract.total_box_remain = 0;
+        contract
+    }
+
+    fn finalize(contract: &mut Contract, block_timestamp: Timestamp) -> Balance {
+        testing_env!(context(&owner()).block_timestamp(block_timestamp).build());
+        contract.finalize_campaign().0
+    }
+
+    #[test]
+    #[should_panic(expected = "[CAMPAIGN_NOT_OVER]")]
+    fn finalize_before_sold_out() {
+        let mut contract = setup_contract();
+
+        finalize(&mut contract, MINT_START_TIMESTAMP);
+    }
+
+    #[test]
+    #[should_panic(expected = "[CAMPAIGN_NOT_OVER]")]
+    fn finalize_before_deadline() {
+        let mut contract = setup_contract();
+        contract.config.campaign_end_timestamp = Some(MINT_START_TIMESTAMP + ONE_DAY);
+
+        finalize(&mut contract, MINT_START_TIMESTAMP + ONE_DAY - 1);
+    }
+
+    #[test]
+    fn finalize_after_deadline() {
+        let mut contract = setup_contract();
+        contract.config.campaign_end_timestamp = Some(MINT_START_TIMESTAMP + ONE_DAY);
+        let lol_tokens_remain = contract.lol_tokens_remain;
+
+        assert_eq!(finalize(&mut contract, MINT_START_TIMESTAMP + ONE_DAY), lol_tokens_remain);
+        assert!(contract.is_campaign_finalized());
+    }
+
+    #[test]
+    #[should_panic(expected = "[UNAUTHORIZED]")]
+    fn finalize_by_not_owner() {
+        let mut contract = setup_sold_out(UnclaimedDestination::Burn);
+        testing_env!(context(&account("alice.near")).build());
+
+        contract.finalize_campaign();
+    }
+
+    #[test]
+    #[should_panic(expected = "[CAMPAIGN_FINALIZED]")]
+    fn finalize_twice() {
+        let mut contract = setup_sold_out(UnclaimedDestination::Burn);
+        finalize(&mut contract, MINT_START_TIMESTAMP);
+
+        finalize(&mut contract, MINT_START_TIMESTAMP);
+    }
+
+    #[test]
+    fn unclaimed_rewards_are_burned() {
+        let mut contract = setup_sold_out(UnclaimedDestination::Burn);
+        let total_supply = contract.ft_total_supply().0;
+        let lol_tokens_remain = contract.lol_tokens_remain;
+
+        assert_eq!(finalize(&mut contract, MINT_START_TIMESTAMP), lol_tokens_remain);
+
+        assert_eq!(contract.lol_tokens_remain, 0);
+        assert_eq!(contract.get_total_stats().4[2].0, 0);
+        assert_eq!(contract.ft_total_supply().0, total_supply - lol_tokens_remain);
+        assert!(get_logs().contains(&format!(
+            r#"EVENT_JSON:{{"standard":"lol","version":"1.0.0","event":"campaign_finalized","data":[{{"destination":"burn","lol_amount":"{}","daily_pool_amount":"0"}}]}}"#,
+            lol_tokens_remain
+        )));
+    }
+
+    #[test]
+    fn unclaimed_rewards_are_moved_to_liquidity() {
+        let mut contract = setup_sold_out(UnclaimedDestination::Liquidity);
+        let lp_account = contract.config.liquidity_account_id();
+        let lp_balance = contract.ft_balance_of(lp_account.clone()).0;
+        let total_supply = contract.ft_total_supply().0;
+        let lol_tokens_remain = contract.lol_tokens_remain;
+
+        finalize(&mut contract, MINT_START_TIMESTAMP);
+
+        assert_eq!(contract.lol_tokens_remain, 0);
+        assert_eq!(contract.ft_balance_of(lp_account).0, lp_balance + lol_tokens_remain);
+        assert_eq!(contract.ft_total_supply().0, total_supply);
+    }
+
+    #[test]
+    fn unclaimed_rewards_are_moved_to_treasury() {
+        let treasury_id = account("treasury.near");
+        let mut contract = setup_sold_out(UnclaimedDestination::Treasury(treasury_id.clone()));
+        let lol_tokens_remain = contract.lol_tokens_remain;
+
+        finalize(&mut contract, MINT_START_TIMESTAMP);
+
+        assert_eq!(contract.lol_tokens_remain, 0);
+        assert_eq!(contract.ft_balance_of(treasury_id).0, lol_tokens_remain);
+        assert!(get_logs().contains(&format!(
+            r#"EVENT_JSON:{{"standard":"lol","version":"1.0.0","event":"campaign_finalized","data":[{{"destination":{{"treasury":"treasury.near"}},"lol_amount":"{}","daily_pool_amount":"0"}}]}}"#,
+            lol_tokens_remain
+        )));
+    }
+}
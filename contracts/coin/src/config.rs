@@ -0,0 +1,177 @@
+use crate::*;
+
+// Where unclaimed LOL box allocation goes when campaign is finalized
+#[derive(Clone, Debug, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum UnclaimedDestination {
+    Burn,
+    Liquidity,
+    Treasury(AccountId),
+}
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InitConfig {
+    pub total_supply: U128,
+    pub box_tokens_amount: U128,
+    pub lp_tokens_amount: U128,
+    pub open_box_price: U128,
+    pub premium_boxes_per_account: u32,
+    pub mint_start_timestamp: Timestamp,
+    // Campaign can be finalized after this time even if boxes are not sold out
+    pub campaign_end_timestamp: Option<Timestamp>,
+    pub unclaimed_destination: UnclaimedDestination,
+    // LP vesting starts at contract initialization if not set
+    pub lp_vesting_start_timestamp: Option<Timestamp>,
+    pub lp_vesting_cliff: Timestamp,
+    pub lp_vesting_duration: Timestamp,
+    // Sub-accounts are created as "<prefix>.<contract>"
+    pub liquidity_account_prefix: String,
+    pub burn_account_prefix: String,
+}
+
+impl InitConfig {
+    // Today's LOL Memecoin values
+    pub fn default_config() -> Self {
+        Self {
+            total_supply: TOTAL_SUPPLY_TOKENS_AMOUNT.into(),
+            box_tokens_amount: (TOTAL_SUPPLY_TOKENS_AMOUNT - LP_TOKENS_AMOUNT).into(),
+            lp_tokens_amount: LP_TOKENS_AMOUNT.into(),
+            open_box_price: OPEN_BOX_PRICE.into(),
+            premium_boxes_per_account: PREMIUM_BOXES_PER_ACCOUNT,
+            mint_start_timestamp: MINT_START_TIMESTAMP.max(env::block_timestamp()),
+            campaign_end_timestamp: None,
+            unclaimed_destination: UnclaimedDestination::Burn,
+            lp_vesting_start_timestamp: None,
+            lp_vesting_cliff: LP_VESTING_CLIFF,
+            lp_vesting_duration: LP_VESTING_DURATION,
+            liquidity_account_prefix: "liquidity".to_string(),
+            burn_account_prefix: "burn".to_string(),
+        }
+    }
+
+    pub fn assert_valid(&self) {
+        if self.box_tokens_amount.0.checked_add(self.lp_tokens_amount.0) != Some(self.total_supply.0) {
+            ContractError::InvalidAllocation.panic();
+        }
+        if self.open_box_price.0 == 0 {
+            ContractError::ZeroPrice.panic();
+        }
+        if self.mint_start_timestamp < env::block_timestamp() {
+            ContractError::StartInPast.panic();
+        }
+        if let Some(campaign_end_timestamp) = self.campaign_end_timestamp {
+            if campaign_end_timestamp <= self.mint_start_timestamp {
+                ContractError::InvalidCampaignEnd.panic();
+            }
+        }
+        if self.lp_vesting_cliff > self.lp_vesting_duration {
+            ContractError::InvalidVestingCliff.panic();
+        }
+        self.liquidity_account_id();
+        self.burn_account_id();
+    }
+
+    pub fn liquidity_account_id(&self) -> AccountId {
+        Self::sub_account_id(&self.liquidity_account_prefix)
+    }
+
+    pub fn burn_account_id(&self) -> AccountId {
+        Self::sub_account_id(&self.burn_account_prefix)
+    }
+
+    fn sub_account_id(prefix: &str) -> AccountId {
+        format!("{}.{}", prefix, env::current_account_id())
+            .try_into()
+            .unwrap_or_else(|_| ContractError::InvalidAccountPrefix.panic())
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_config(&self) -> &InitConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+
+    // Default config modified by update, validated a day before mint start
+    fn assert_valid_with(update: impl FnOnce(&mut InitConfig)) {
+        testing_env!(context(&owner()).block_timestamp(MINT_START_TIMESTAMP - ONE_DAY).build());
+        let mut config = InitConfig::default_config();
+        update(&mut config);
+        config.assert_valid();
+    }
+
+    #[test]
+    fn default_config_keeps_launch_values() {
+        let contract = setup_contract();
+
+        assert_eq!(contract.ft_total_supply().0, TOTAL_SUPPLY_TOKENS_AMOUNT);
+        assert_eq!(contract.ft_balance_of(contract_account()).0, TOTAL_SUPPLY_TOKENS_AMOUNT);
+        assert_eq!(contract.lol_tokens_remain, TOTAL_SUPPLY_TOKENS_AMOUNT - LP_TOKENS_AMOUNT);
+        assert_eq!(contract.get_lp_vesting().total_amount.0, LP_TOKENS_AMOUNT);
+        assert_eq!(contract.get_lp_vesting().beneficiary_id, account("liquidity.lol.near"));
+        assert!(contract.storage_balance_of(account("burn.lol.near")).is_none());
+        assert_eq!(contract.get_box_price(account("alice.near")).0, OPEN_BOX_PRICE);
+        assert_eq!(contract.user_premium_boxes_left(account("alice.near")), PREMIUM_BOXES_PER_ACCOUNT);
+        assert_eq!(contract.get_config().mint_start_timestamp, MINT_START_TIMESTAMP);
+        let stats = contract.get_total_stats();
+        assert_eq!(stats.3, 50000);
+        assert_eq!(stats.4, vec![U128(TOTAL_SUPPLY_TOKENS_AMOUNT), U128(LP_TOKENS_AMOUNT), U128(TOTAL_SUPPLY_TOKENS_AMOUNT - LP_TOKENS_AMOUNT)]);
+        assert_eq!(stats.6, MINT_START_TIMESTAMP);
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert_valid_with(|_| {});
+    }
+
+    #[test]
+    #[should_panic(expected = "[INVALID_ALLOCATION]")]
+    fn allocations_not_summing_to_supply() {
+        assert_valid_with(|config| config.lp_tokens_amount = U128(config.lp_tokens_amount.0 + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "[INVALID_ALLOCATION]")]
+    fn allocations_overflow() {
+        assert_valid_with(|config| config.lp_tokens_amount = U128(u128::MAX));
+    }
+
+    #[test]
+    #[should_panic(expected = "[ZERO_PRICE]")]
+    fn zero_box_price() {
+        assert_valid_with(|config| config.open_box_price = U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "[START_IN_PAST]")]
+    fn start_in_past() {
+        assert_valid_with(|config| config.mint_start_timestamp = MINT_START_TIMESTAMP - 2 * ONE_DAY);
+    }
+
+    #[test]
+    #[should_panic(expected = "[INVALID_CAMPAIGN_END]")]
+    fn campaign_end_before_start() {
+        assert_valid_with(|config| config.campaign_end_timestamp = Some(config.mint_start_timestamp));
+    }
+
+    #[test]
+    #[should_panic(expected = "[INVALID_VESTING_CLIFF]")]
+    fn vesting_cliff_after_duration() {
+        assert_valid_with(|config| config.lp_vesting_cliff = config.lp_vesting_duration + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "[INVALID_ACCOUNT_PREFIX]")]
+    fn invalid_account_prefix() {
+        assert_valid_with(|config| config.liquidity_account_prefix = "LP pool".to_string());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::*;
+
+pub const DAILY_BOX_COOLDOWN: Timestamp = ONE_DAY;
+pub const DAILY_BOX_LOL_MIN: u32 = 100;
+pub const DAILY_BOX_LOL_MAX: u32 = 1000;
+
+#[near_bindgen]
+impl Contract {
+    // Free basic box once per day, LOL reward is paid from daily pool.
+    // Account must be registered with storage_deposit, as free box doesn't pay for storage
+    pub fn claim_daily_box(&mut self) -> U128 {
+        let owner_id = env::predecessor_account_id();
+        let now = env::block_timestamp();
+        if self.campaign_finalized {
+            ContractError::CampaignFinalized.panic();
+        }
+        self._assert_not_paused();
+        if self._current_phase() == Phase::NotStarted {
+            ContractError::NotStarted.panic();
+        }
+        if !self.token.accounts.contains_key(&owner_id) {
+            ContractError::NotRegistered.panic();
+        }
+        if now < self.next_daily_box_at(owner_id.clone()) {
+            ContractError::DailyBoxCooldown.panic();
+        }
+
+        let lol_amount = (self.random_in_range(2, (DAILY_BOX_LOL_MAX - DAILY_BOX_LOL_MIN) as usize) + DAILY_BOX_LOL_MIN) as u128 * ONE_TOKEN;
+        if self.daily_lol_pool < lol_amount {
+            ContractError::DailyPoolEmpty.panic();
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        let mut user = self._get_user(&owner_id);
+        // Streak continues when previous daily box was claimed less than 2 days ago
+        if user.last_daily_box_at > 0 && now < user.last_daily_box_at + 2 * DAILY_BOX_COOLDOWN {
+            user.daily_streak += 1;
+        } else {
+            user.daily_streak = 1;
+        }
+        user.last_daily_box_at = now;
+        user.daily_boxes_opened += 1;
+        user.lol_reward += lol_amount;
+        self._save_user(&owner_id, &user);
+
+        self._update_leaderboard("lol_leaderboard", &owner_id, user.lol_reward);
+        self.daily_lol_pool -= lol_amount;
+        self.token.internal_transfer(&env::current_account_id(), &owner_id, lol_amount, None);
+        self._record_storage_usage(&owner_id, initial_storage_usage);
+
+        log!("Daily box: {}, {}, {}", owner_id, lol_amount, user.daily_streak);
+        lol_amount.into()
+    }
+
+    pub fn next_daily_box_at(&self, account_id: AccountId) -> Timestamp {
+        let last_daily_box_at = self._get_user(&account_id).last_daily_box_at;
+        if last_daily_box_at == 0 {
+            return 0;
+        }
+        last_daily_box_at + DAILY_BOX_COOLDOWN
+    }
+
+    pub fn get_daily_streak(&self, account_id: AccountId) -> u32 {
+        self._get_user(&account_id).daily_streak
+    }
+
+    pub fn get_daily_boxes_opened(&self, account_id: AccountId) -> u32 {
+        self._get_user(&account_id).daily_boxes_opened
+    }
+
+    pub fn get_daily_lol_pool(&self) -> U128 {
+        self.daily_lol_pool.into()
+    }
+
+    // -------------- Admin functions --------------
+
+    // Move LOL from box rewards allocation to daily boxes pool
+    pub fn allocate_daily_pool(&mut self, amount: U128) -> U128 {
+        self._assert_owner();
+        if amount.0 == 0 || amount.0 > self.lol_tokens_remain {
+            ContractError::WrongAmount.panic();
+        }
+
+        self.lol_tokens_remain -= amount.0;
+        self.daily_lol_pool += amount.0;
+        log!("Daily pool allocated: {}", amount.0);
+
+        self.daily_lol_pool.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+
+    // Registered account and daily pool with given amount of LOL tokens
+    fn setup_daily(pool_tokens: u128) -> (Contract, AccountId) {
+        let mut contract = setup_contract();
+        contract.allocate_daily_pool((pool_tokens * ONE_TOKEN).into());
+        let alice = account("alice.near");
+        testing_env!(context(&alice).attached_deposit(contract.storage_balance_bounds().min.0).build());
+        contract.storage_deposit(None, None);
+        (contract, alice)
+    }
+
+    fn claim_at(contract: &mut Contract, account_id: &AccountId, timestamp: Timestamp) -> U128 {
+        testing_env!(context(account_id).block_timestamp(timestamp).build());
+        contract.claim_daily_box()
+    }
+
+    #[test]
+    fn claim_daily_box_pays_from_pool() {
+        let (mut contract, alice) = setup_daily(10_000);
+
+        let amount = claim_at(&mut contract, &alice, MINT_START_TIMESTAMP).0;
+
+        assert!(amount >= DAILY_BOX_LOL_MIN as u128 * ONE_TOKEN && amount < DAILY_BOX_LOL_MAX as u128 * ONE_TOKEN);
+        assert_eq!(contract.ft_balance_of(alice.clone()).0, amount);
+        assert_eq!(contract.get_daily_lol_pool().0, 10_000 * ONE_TOKEN - amount);
+        assert_eq!(contract.get_daily_boxes_opened(alice.clone()), 1);
+        assert_eq!(contract.get_daily_streak(alice), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "[DAILY_BOX_COOLDOWN]")]
+    fn claim_daily_box_twice_a_day() {
+        let (mut contract, alice) = setup_daily(10_000);
+        claim_at(&mut contract, &alice, MINT_START_TIMESTAMP);
+
+        claim_at(&mut contract, &alice, MINT_START_TIMESTAMP + DAILY_BOX_COOLDOWN - 1);
+    }
+
+    #[test]
+    fn next_daily_box_at_follows_claims() {
+        let (mut contract, alice) = setup_daily(10_000);
+        assert_eq!(contract.next_daily_box_at(alice.clone()), 0);
+
+        claim_at(&mut contract, &alice, MINT_START_TIMESTAMP);
+        let next_daily_box_at = contract.next_daily_box_at(alice.clone());
+        assert_eq!(next_daily_box_at, MINT_START_TIMESTAMP + DAILY_BOX_COOLDOWN);
+
+        claim_at(&mut contract, &alice, next_daily_box_at);
+        assert_eq!(contract.get_daily_streak(alice.clone()), 2);
+
+        // Streak is reset after a missed day
+        claim_at(&mut contract, &alice, next_daily_box_at + 2 * DAILY_BOX_COOLDOWN);
+        assert_eq!(contract.get_daily_streak(alice), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "[DAILY_POOL_EMPTY]")]
+    fn claim_daily_box_from_exhausted_pool() {
+        let (mut contract, alice) = setup_daily(DAILY_BOX_LOL_MIN as u128 - 1);
+
+        claim_at(&mut contract, &alice, MINT_START_TIMESTAMP);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOT_REGISTERED]")]
+    fn claim_daily_box_without_registration() {
+        let (mut contract, _) = setup_daily(10_000);
+
+        claim_at(&mut contract, &account("bob.near"), MINT_START_TIMESTAMP);
+    }
+
+    #[test]
+    fn daily_box_is_basic_box() {
+        let (mut contract, alice) = setup_daily(10_000);
+        let total_box_remain = contract.total_box_remain;
+        let rewards_remain = contract.rewards_remain.clone();
+        let lol_tokens_remain = contract.lol_tokens_remain;
+
+        let amount = claim_at(&mut contract, &alice, MINT_START_TIMESTAMP).0;
+
+        // No NEAR prize, premium box or box supply is used by daily box
+        assert_eq!(contract.get_user_rewards(alice.clone()), (U128(amount), U128(0), 0));
+        assert_eq!(contract.user_premium_boxes_left(alice), contract.config.premium_boxes_per_account);
+        assert_eq!(contract.total_box_remain, total_box_remain);
+        assert_eq!(contract.rewards_remain, rewards_remain);
+        assert_eq!(contract.lol_tokens_remain, lol_tokens_remain);
+    }
+
+    #[test]
+    fn daily_pool_is_swept_by_finalization() {
+        let (mut contract, _) = setup_daily(10_000);
+        contract.total_box_remain = 0;
+        let lol_tokens_remain = contract.lol_tokens_remain;
+        let total_supply = contract.ft_total_supply().0;
+
+        testing_env!(context(&owner()).build());
+        let amount = contract.finalize_campaign().0;
+
+        assert_eq!(amount, lol_tokens_remain + 10_000 * ONE_TOKEN);
+        assert_eq!(contract.get_daily_lol_pool().0, 0);
+        assert_eq!(contract.ft_total_supply().0, total_supply - amount);
+    }
+}
@@ -0,0 +1,290 @@
+use crate::*;
+use std::fmt;
+
+// Panics are rendered as "Error: [CODE] message", frontends should match by stable CODE
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContractError {
+    Unauthorized,
+    WrongDeposit,
+    SoldOut,
+    NotStarted,
+    Paused,
+    CampaignFinalized,
+    CampaignNotOver,
+    NotAllowlisted,
+    AccountLimitReached,
+    AccountBlockLimitReached,
+    AccountDailyLimitReached,
+    GlobalBlockLimitReached,
+    WrongAmount,
+    WrongBoxesCount,
+    WrongBatchSize,
+    WrongMessage,
+    WrongToken,
+    WrongLeaderboard,
+    LolPaymentDisabled,
+    NothingToRelease,
+    PremiumOverflow,
+    GrantExpired,
+    SelfReferral,
+    ReferralCycle,
+    ReferralChainTooDeep,
+    VerifierNotUsed,
+    NotRelayed,
+    NoWelcomeBoxes,
+    WelcomeBoxClaimed,
+    DailyBoxCooldown,
+    DailyPoolEmpty,
+    NoGiftedBoxes,
+    TokenNotFound,
+    NotTokenOwner,
+    SoulboundToken,
+    StakeNotFound,
+    StakeLocked,
+    AlreadyUnstaking,
+    WrongStakeDuration,
+    TooManyStakes,
+    NothingToClaim,
+    NotRegistered,
+    NotInitialized,
+    AlreadyInitialized,
+    StakeStorageRequired,
+    InvalidAllocation,
+    ZeroPrice,
+    StartInPast,
+    InvalidCampaignEnd,
+    InvalidVestingCliff,
+    InvalidAccountPrefix,
+    InvalidPhases,
+    InvalidReferralConfig,
+    InvalidRevenueShare,
+    InvalidJackpotChance,
+}
+
+impl ContractError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ContractError::Unauthorized => "UNAUTHORIZED",
+            ContractError::WrongDeposit => "WRONG_DEPOSIT",
+            ContractError::SoldOut => "SOLD_OUT",
+            ContractError::NotStarted => "NOT_STARTED",
+            ContractError::Paused => "PAUSED",
+            ContractError::CampaignFinalized => "CAMPAIGN_FINALIZED",
+            ContractError::CampaignNotOver => "CAMPAIGN_NOT_OVER",
+            ContractError::NotAllowlisted => "NOT_ALLOWLISTED",
+            ContractError::AccountLimitReached => "ACCOUNT_LIMIT_REACHED",
+            ContractError::AccountBlockLimitReached => "ACCOUNT_BLOCK_LIMIT_REACHED",
+            ContractError::AccountDailyLimitReached => "ACCOUNT_DAILY_LIMIT_REACHED",
+            ContractError::GlobalBlockLimitReached => "GLOBAL_BLOCK_LIMIT_REACHED",
+            ContractError::WrongAmount => "WRONG_AMOUNT",
+            ContractError::WrongBoxesCount => "WRONG_BOXES_COUNT",
+            ContractError::WrongBatchSize => "WRONG_BATCH_SIZE",
+            ContractError::WrongMessage => "WRONG_MESSAGE",
+            ContractError::WrongToken => "WRONG_TOKEN",
+            ContractError::WrongLeaderboard => "WRONG_LEADERBOARD",
+            ContractError::LolPaymentDisabled => "LOL_PAYMENT_DISABLED",
+            ContractError::NothingToRelease => "NOTHING_TO_RELEASE",
+            ContractError::PremiumOverflow => "PREMIUM_OVERFLOW",
+            ContractError::GrantExpired => "GRANT_EXPIRED",
+            ContractError::SelfReferral => "SELF_REFERRAL",
+            ContractError::ReferralCycle => "REFERRAL_CYCLE",
+            ContractError::ReferralChainTooDeep => "REFERRAL_CHAIN_TOO_DEEP",
+            ContractError::VerifierNotUsed => "VERIFIER_NOT_USED",
+            ContractError::NotRelayed => "NOT_RELAYED",
+            ContractError::NoWelcomeBoxes => "NO_WELCOME_BOXES",
+            ContractError::WelcomeBoxClaimed => "WELCOME_BOX_CLAIMED",
+            ContractError::DailyBoxCooldown => "DAILY_BOX_COOLDOWN",
+            ContractError::DailyPoolEmpty => "DAILY_POOL_EMPTY",
+            ContractError::NoGiftedBoxes => "NO_GIFTED_BOXES",
+            ContractError::TokenNotFound => "TOKEN_NOT_FOUND",
+            ContractError::NotTokenOwner => "NOT_TOKEN_OWNER",
+            ContractError::SoulboundToken => "SOULBOUND_TOKEN",
+            ContractError::StakeNotFound => "STAKE_NOT_FOUND",
+            ContractError::StakeLocked => "STAKE_LOCKED",
+            ContractError::AlreadyUnstaking => "ALREADY_UNSTAKING",
+            ContractError::WrongStakeDuration => "WRONG_STAKE_DURATION",
+            ContractError::TooManyStakes => "TOO_MANY_STAKES",
+            ContractError::NothingToClaim => "NOTHING_TO_CLAIM",
+            ContractError::NotRegistered => "NOT_REGISTERED",
+            ContractError::NotInitialized => "NOT_INITIALIZED",
+            ContractError::AlreadyInitialized => "ALREADY_INITIALIZED",
+            ContractError::StakeStorageRequired => "STAKE_STORAGE_REQUIRED",
+            ContractError::InvalidAllocation => "INVALID_ALLOCATION",
+            ContractError::ZeroPrice => "ZERO_PRICE",
+            ContractError::StartInPast => "START_IN_PAST",
+            ContractError::InvalidCampaignEnd => "INVALID_CAMPAIGN_END",
+            ContractError::InvalidVestingCliff => "INVALID_VESTING_CLIFF",
+            ContractError::InvalidAccountPrefix => "INVALID_ACCOUNT_PREFIX",
+            ContractError::InvalidPhases => "INVALID_PHASES",
+            ContractError::InvalidReferralConfig => "INVALID_REFERRAL_CONFIG",
+            ContractError::InvalidRevenueShare => "INVALID_REVENUE_SHARE",
+            ContractError::InvalidJackpotChance => "INVALID_JACKPOT_CHANCE",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            ContractError::Unauthorized => "only owner can call this method",
+            ContractError::WrongDeposit => "Wrong open deposit",
+            ContractError::SoldOut => "No boxes remains",
+            ContractError::NotStarted => "Too early to open boxes",
+            ContractError::Paused => "Boxes sale is paused",
+            ContractError::CampaignFinalized => "Campaign is finalized",
+            ContractError::CampaignNotOver => "Campaign is not over",
+            ContractError::NotAllowlisted => "Account is not in allowlist",
+            ContractError::AccountLimitReached => "Account boxes limit reached",
+            ContractError::AccountBlockLimitReached => "Account block limit reached",
+            ContractError::AccountDailyLimitReached => "Account daily limit reached",
+            ContractError::GlobalBlockLimitReached => "Global block limit reached",
+            ContractError::WrongAmount => "Wrong amount",
+            ContractError::WrongBoxesCount => "Wrong boxes count",
+            ContractError::WrongBatchSize => "Wrong batch size",
+            ContractError::WrongMessage => "Wrong transfer message",
+            ContractError::WrongToken => "only LOL tokens are accepted",
+            ContractError::WrongLeaderboard => "wrong leaderboard type",
+            ContractError::LolPaymentDisabled => "Paying with LOL is disabled",
+            ContractError::NothingToRelease => "Nothing to release",
+            ContractError::PremiumOverflow => "Premium allowance overflow",
+            ContractError::GrantExpired => "Premium grant is already expired",
+            ContractError::SelfReferral => "Self-referral is not allowed",
+            ContractError::ReferralCycle => "Referral cycle is not allowed",
+            ContractError::ReferralChainTooDeep => "Referrer chain is too long",
+            ContractError::VerifierNotUsed => "Verifier contract is not used",
+            ContractError::NotRelayed => "Welcome box must be sent by approved relayer",
+            ContractError::NoWelcomeBoxes => "No welcome boxes remains",
+            ContractError::WelcomeBoxClaimed => "Welcome box is only for new users",
+            ContractError::DailyBoxCooldown => "Daily box is already claimed today",
+            ContractError::DailyPoolEmpty => "Daily boxes pool is empty",
+            ContractError::NoGiftedBoxes => "No gifted boxes",
+            ContractError::TokenNotFound => "Box NFT not found",
+            ContractError::NotTokenOwner => "Only box NFT owner can call this method",
+            ContractError::SoulboundToken => "Achievement badges can't be transferred or opened",
+            ContractError::StakeNotFound => "Stake position not found",
+            ContractError::StakeLocked => "Stake is locked",
+            ContractError::AlreadyUnstaking => "Stake is already unstaking",
+            ContractError::WrongStakeDuration => "Wrong stake duration",
+            ContractError::TooManyStakes => "Too many stake positions",
+            ContractError::NothingToClaim => "Nothing to claim",
+            ContractError::NotRegistered => "Account is not registered",
+            ContractError::NotInitialized => "Contract is not initialized",
+            ContractError::AlreadyInitialized => "Already initialized",
+            ContractError::StakeStorageRequired => "Not enough NEAR deposited for stake storage",
+            ContractError::InvalidAllocation => "Token allocations must sum to total supply",
+            ContractError::ZeroPrice => "Open box price must be non-zero",
+            ContractError::StartInPast => "Mint start must be in the future",
+            ContractError::InvalidCampaignEnd => "Campaign end must be after mint start",
+            ContractError::InvalidVestingCliff => "Vesting cliff is longer than duration",
+            ContractError::InvalidAccountPrefix => "Wrong sub-account prefix",
+            ContractError::InvalidPhases => "Allowlist phase must start before public phase",
+            ContractError::InvalidReferralConfig => "Wrong referral bonus",
+            ContractError::InvalidRevenueShare => "Staking and jackpot shares are more than 100%",
+            ContractError::InvalidJackpotChance => "Jackpot chance is more than 100%",
+        }
+    }
+
+    // Aborts the call with rendered error. The cfg!(test) branch is intentional: mocked blockchain can't unwind
+    // from env::panic_str, so unit tests get regular panic with the same message to match codes in should_panic
+    pub fn panic(self) -> ! {
+        if cfg!(test) {
+            panic!("{}", self);
+        }
+        env::panic_str(&self.to_string())
+    }
+}
+
+impl fmt::Display for ContractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Error: [{}] {}", self.code(), self.message())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn error_is_rendered_with_code() {
+        assert_eq!(ContractError::SoldOut.to_string(), "Error: [SOLD_OUT] No boxes remains");
+        assert_eq!(ContractError::AlreadyInitialized.to_string(), "Error: [ALREADY_INITIALIZED] Already initialized");
+    }
+
+    #[test]
+    #[should_panic(expected = "[ALREADY_INITIALIZED]")]
+    fn init_twice() {
+        let contract = setup_contract();
+        env::state_write(&contract);
+
+        Contract::new_default_meta(owner());
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOT_INITIALIZED]")]
+    fn migrate_without_state() {
+        testing_env!(context(&contract_account()).build());
+
+        Contract::migrate();
+    }
+
+    #[test]
+    #[should_panic(expected = "[UNAUTHORIZED]")]
+    fn admin_method_by_user() {
+        let mut contract = setup_contract();
+        testing_env!(context(&account("alice.near")).build());
+
+        contract.set_lol_box_price(U128(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "[WRONG_DEPOSIT]")]
+    fn open_box_without_deposit() {
+        let mut contract = setup_contract();
+        testing_env!(context(&account("alice.near")).build());
+
+        contract.open_box(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOT_STARTED]")]
+    fn open_box_before_mint_start() {
+        let mut contract = setup_contract();
+        testing_env!(context(&account("alice.near"))
+            .block_timestamp(MINT_START_TIMESTAMP - 1)
+            .attached_deposit(OPEN_BOX_PRICE)
+            .build());
+
+        contract.open_box(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "[PAUSED]")]
+    fn open_box_when_paused() {
+        let mut contract = setup_contract();
+        contract.set_paused(true);
+
+        open_box(&mut contract, &account("alice.near"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "[PAUSED]")]
+    fn buy_boxes_when_paused() {
+        let mut contract = setup_contract();
+        contract.set_paused(true);
+        testing_env!(context(&account("alice.near")).attached_deposit(2 * OPEN_BOX_PRICE).build());
+
+        contract.buy_boxes(1);
+    }
+
+    #[test]
+    fn open_box_after_unpause() {
+        let mut contract = setup_contract();
+        contract.set_paused(true);
+        contract.set_paused(false);
+
+        open_box(&mut contract, &account("alice.near"), None);
+
+        assert!(!contract.is_paused());
+        assert_eq!(contract.get_user_rewards(account("alice.near")).2, 1);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::*;
+use near_sdk::serde_json;
+
+pub const EVENT_STANDARD: &str = "lol";
+pub const EVENT_VERSION: &str = "1.0.0";
+
+// NEP-297 events of contract features that are not covered by FT and NFT standards
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data")]
+#[serde(rename_all = "snake_case")]
+pub enum LolEvent<'a> {
+    PremiumGranted(Vec<PremiumGrantedData<'a>>),
+    PremiumRevoked(Vec<PremiumRevokedData<'a>>),
+    CampaignFinalized(Vec<CampaignFinalizedData<'a>>),
+    JackpotWon(Vec<JackpotWonData<'a>>),
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PremiumGrantedData<'a> {
+    pub account_id: &'a AccountId,
+    pub amount: u32,
+    // None - grant doesn't expire
+    pub expires_at: Option<Timestamp>,
+    // Extra premium boxes of account after grant
+    pub additional_premium: u32,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PremiumRevokedData<'a> {
+    pub account_id: &'a AccountId,
+    pub amount: u32,
+    pub additional_premium: u32,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CampaignFinalizedData<'a> {
+    pub destination: &'a UnclaimedDestination,
+    // Unclaimed box allocation and daily boxes pool moved to destination
+    pub lol_amount: U128,
+    pub daily_pool_amount: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JackpotWonData<'a> {
+    pub account_id: &'a AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a LolEvent<'a>,
+}
+
+impl LolEvent<'_> {
+    pub fn emit(&self) {
+        let event = EventLog { standard: EVENT_STANDARD, version: EVENT_VERSION, event: self };
+        log!("EVENT_JSON:{}", serde_json::to_string(&event).unwrap());
+    }
+}
@@ -0,0 +1,254 @@
+use crate::*;
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GiftedBoxes {
+    pub sender_id: AccountId,
+    pub count: u32,
+    // Price paid by sender for each box, refunded if campaign ends before box is opened
+    pub price: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Buy boxes for another account, receiver opens them later with open_gifted_box.
+    // Allowlist and account limit are checked for receiver. Deposit over boxes price covers storage
+    #[payable]
+    pub fn gift_boxes(&mut self, receiver_id: AccountId, count: u32) {
+        let sender_id = env::predecessor_account_id();
+        let price = self._box_price(&sender_id);
+        if count == 0 || count > MAX_BOXES_PER_CALL || sender_id == receiver_id {
+            ContractError::WrongBoxesCount.panic();
+        }
+        if env::attached_deposit() < price * count as u128 {
+            ContractError::WrongDeposit.panic();
+        }
+        self._assert_can_reserve_boxes(&receiver_id, count);
+        let initial_storage_usage = env::storage_usage();
+
+        let mut gifts = self.gifted_boxes.get(&receiver_id).unwrap_or_default();
+        match gifts.iter_mut().find(|gift| gift.sender_id == sender_id && gift.price.0 == price) {
+            Some(gift) => gift.count += count,
+            None => gifts.push(GiftedBoxes { sender_id: sender_id.clone(), count, price: price.into() }),
+        }
+        self.gifted_boxes.insert(&receiver_id, &gifts);
+
+        let escrow = price * count as u128;
+        let storage_fee = self._record_storage_usage(&sender_id, initial_storage_usage);
+        if env::attached_deposit() < escrow + storage_fee {
+            ContractError::WrongDeposit.panic();
+        }
+        self.total_box_reserved += count;
+        self.treasury.box_escrow += escrow;
+        self.treasury.storage_fees += storage_fee;
+        self._collect_box_payment(env::attached_deposit() - escrow - storage_fee);
+
+        log!("Boxes gifted: {}, {}, {}", sender_id, receiver_id, count);
+    }
+
+    pub fn open_gifted_box(&mut self) -> (usize, U128, U128) {
+        let owner_id = env::predecessor_account_id();
+        let mut gifts = self.gifted_boxes.get(&owner_id).unwrap_or_default();
+        if gifts.is_empty() {
+            ContractError::NoGiftedBoxes.panic();
+        }
+        self._assert_can_open_reserved_box(&owner_id);
+
+        let price = gifts[0].price.0;
+        gifts[0].count -= 1;
+        if gifts[0].count == 0 {
+            gifts.remove(0);
+        }
+        if gifts.is_empty() {
+            self.gifted_boxes.remove(&owner_id);
+        } else {
+            self.gifted_boxes.insert(&owner_id, &gifts);
+        }
+
+        self.total_box_reserved -= 1;
+        self.treasury.box_escrow -= price;
+
+        let initial_storage_usage = env::storage_usage();
+        let result = self._open_box(owner_id.clone(), BoxPayment::Near(price));
+
+        // Storage fee is deducted from gifted box price
+        let storage_fee = self._record_storage_usage(&owner_id, initial_storage_usage).min(price);
+        self.treasury.storage_fees += storage_fee;
+        self._collect_box_payment(price - storage_fee);
+
+        result
+    }
+
+    // Sender gets paid price back for boxes that were not opened before campaign finalization
+    pub fn refund_gifted_boxes(&mut self, receiver_id: AccountId) -> U128 {
+        if !self.campaign_finalized {
+            ContractError::CampaignNotOver.panic();
+        }
+
+        let sender_id = env::predecessor_account_id();
+        let mut gifts = self.gifted_boxes.get(&receiver_id).unwrap_or_default();
+        let mut refund_count: u32 = 0;
+        let mut refund_amount: Balance = 0;
+        gifts.retain(|gift| {
+            if gift.sender_id != sender_id {
+                return true;
+            }
+            refund_count += gift.count;
+            refund_amount += gift.price.0 * gift.count as u128;
+            false
+        });
+        if refund_count == 0 {
+            ContractError::NoGiftedBoxes.panic();
+        }
+
+        if gifts.is_empty() {
+            self.gifted_boxes.remove(&receiver_id);
+        } else {
+            self.gifted_boxes.insert(&receiver_id, &gifts);
+        }
+        self.total_box_reserved -= refund_count;
+        self.treasury.box_escrow -= refund_amount;
+
+        log!("Gifted boxes refunded: {}, {}, {}", sender_id, receiver_id, refund_amount);
+        Promise::new(sender_id).transfer(refund_amount);
+
+        refund_amount.into()
+    }
+
+    pub fn get_gifted_boxes(&self, account_id: AccountId) -> Vec<GiftedBoxes> {
+        self.gifted_boxes.get(&account_id).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+    use near_sdk::test_utils::get_created_receipts;
+    use near_sdk::mock::VmAction;
+
+    // Deposit over boxes price that covers storage of gifts
+    const STORAGE_DEPOSIT: Balance = ONE_TOKEN / 10;
+
+    fn sender() -> AccountId {
+        account("bob.near")
+    }
+
+    fn receiver() -> AccountId {
+        account("alice.near")
+    }
+
+    fn gift(contract: &mut Contract, count: u32) {
+        testing_env!(context(&sender()).attached_deposit(OPEN_BOX_PRICE * count as u128 + STORAGE_DEPOSIT).build());
+        contract.gift_boxes(receiver(), count);
+    }
+
+    fn refund(contract: &mut Contract) -> Balance {
+        testing_env!(context(&sender()).build());
+        contract.refund_gifted_boxes(receiver()).0
+    }
+
+    #[test]
+    fn gift_boxes_are_reserved_for_receiver() {
+        let mut contract = setup_contract();
+
+        gift(&mut contract, 2);
+
+        let gifts = contract.get_gifted_boxes(receiver());
+        assert_eq!(gifts.len(), 1);
+        assert_eq!((gifts[0].sender_id.clone(), gifts[0].count, gifts[0].price.0), (sender(), 2, OPEN_BOX_PRICE));
+        assert_eq!(contract.total_box_reserved, 2);
+
+        // Storage of gifts is charged from sender, the rest of deposit over price is box payment
+        let storage_fee = contract._get_user(&sender()).storage_used as u128 * env::storage_byte_cost();
+        let treasury = contract.get_treasury();
+        assert_eq!(treasury.box_escrow.0, 2 * OPEN_BOX_PRICE);
+        assert_eq!(treasury.storage_fees.0, storage_fee);
+        assert_eq!(treasury.total_collected.0, STORAGE_DEPOSIT - storage_fee);
+    }
+
+    #[test]
+    fn receiver_opens_gifted_box() {
+        let mut contract = setup_contract();
+        gift(&mut contract, 2);
+
+        testing_env!(context(&receiver()).build());
+        contract.open_gifted_box();
+
+        assert_eq!(contract.get_user_rewards(receiver()).2, 1);
+        assert_eq!(contract.get_user_rewards(sender()).2, 0);
+        assert_eq!(contract.get_gifted_boxes(receiver())[0].count, 1);
+        assert_eq!(contract.total_box_reserved, 1);
+        assert_eq!(contract.get_treasury().box_escrow.0, OPEN_BOX_PRICE);
+    }
+
+    #[test]
+    fn gifted_box_uses_premium_cap_of_receiver() {
+        let mut contract = setup_contract();
+        // Every box that can be premium gets NEAR prize
+        contract.rewards_remain[1] = contract.total_box_remain;
+        for account_id in [sender(), receiver()] {
+            let mut user = contract._get_user(&account_id);
+            user.premium_box_opened = contract.config.premium_boxes_per_account;
+            contract._save_user(&account_id, &user);
+        }
+        let carol = account("carol.near");
+        gift(&mut contract, 1);
+        testing_env!(context(&sender()).attached_deposit(OPEN_BOX_PRICE + STORAGE_DEPOSIT).build());
+        contract.gift_boxes(carol.clone(), 1);
+
+        testing_env!(context(&receiver()).build());
+        assert_eq!(contract.open_gifted_box().0, 0);
+        testing_env!(context(&carol).build());
+        assert_eq!(contract.open_gifted_box().0, 1);
+        assert_eq!(contract.user_premium_boxes_left(carol), contract.config.premium_boxes_per_account - 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NO_GIFTED_BOXES]")]
+    fn open_without_gifted_boxes() {
+        let mut contract = setup_contract();
+        testing_env!(context(&receiver()).build());
+
+        contract.open_gifted_box();
+    }
+
+    #[test]
+    fn sender_is_refunded_after_finalization() {
+        let mut contract = setup_contract();
+        gift(&mut contract, 2);
+        contract.campaign_finalized = true;
+
+        assert_eq!(refund(&mut contract), 2 * OPEN_BOX_PRICE);
+
+        assert!(contract.get_gifted_boxes(receiver()).is_empty());
+        assert_eq!(contract.total_box_reserved, 0);
+        assert_eq!(contract.get_treasury().box_escrow.0, 0);
+        let transfer = get_created_receipts().into_iter().find(|receipt| receipt.receiver_id == sender());
+        assert!(matches!(
+            transfer.unwrap().actions[..],
+            [VmAction::Transfer { deposit }] if deposit == 2 * OPEN_BOX_PRICE
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "[CAMPAIGN_NOT_OVER]")]
+    fn sender_is_not_refunded_before_finalization() {
+        let mut contract = setup_contract();
+        gift(&mut contract, 2);
+
+        refund(&mut contract);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NO_GIFTED_BOXES]")]
+    fn sender_is_refunded_once() {
+        let mut contract = setup_contract();
+        gift(&mut contract, 2);
+        contract.campaign_finalized = true;
+        refund(&mut contract);
+
+        refund(&mut contract);
+    }
+}
@@ -0,0 +1,292 @@
+use crate::*;
+use near_sdk::assert_one_yocto;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InventoryInfo {
+    pub count: u32,
+    pub paid: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Buy boxes now and open them later, boxes are reserved from total_box_remain
+    #[payable]
+    pub fn buy_boxes(&mut self, count: u32) -> u32 {
+        let owner_id = env::predecessor_account_id();
+        let price = self._box_price(&owner_id);
+        if count == 0 || count > MAX_BOXES_PER_CALL {
+            ContractError::WrongBoxesCount.panic();
+        }
+        if env::attached_deposit() < price * count as u128 {
+            ContractError::WrongDeposit.panic();
+        }
+
+        self._assert_can_reserve_boxes(&owner_id, count);
+        let initial_storage_usage = env::storage_usage();
+
+        let escrow = price * count as u128;
+        let mut user = self._get_user(&owner_id);
+        user.inventory_count += count;
+        user.inventory_paid += escrow;
+        self._save_user(&owner_id, &user);
+
+        // Deposit over boxes price covers storage, the rest is collected as box payment
+        let storage_fee = self._record_storage_usage(&owner_id, initial_storage_usage);
+        if env::attached_deposit() < escrow + storage_fee {
+            ContractError::WrongDeposit.panic();
+        }
+        self.total_box_reserved += count;
+        self.treasury.box_escrow += escrow;
+        self.treasury.storage_fees += storage_fee;
+        self._collect_box_payment(env::attached_deposit() - escrow - storage_fee);
+
+        log!("Boxes bought: {}, {}", owner_id, count);
+        user.inventory_count
+    }
+
+    pub fn open_from_inventory(&mut self, count: u32) -> Vec<(usize, U128, U128)> {
+        let owner_id = env::predecessor_account_id();
+        let inventory_count = self._get_user(&owner_id).inventory_count;
+        if count == 0 || count > MAX_BOXES_PER_CALL || count > inventory_count {
+            ContractError::WrongBoxesCount.panic();
+        }
+        self._assert_can_open_reserved_box(&owner_id);
+
+        let initial_storage_usage = env::storage_usage();
+        let mut results = vec![];
+        let mut paid: Balance = 0;
+        while (results.len() as u32) < count && self._rate_limit_boxes_left(&owner_id) > 0 {
+            let mut user = self._get_user(&owner_id);
+            let price = user.inventory_paid / user.inventory_count as u128;
+            user.inventory_count -= 1;
+            user.inventory_paid -= price;
+            self._save_user(&owner_id, &user);
+
+            self.total_box_reserved -= 1;
+            self.treasury.box_escrow -= price;
+            paid += price;
+
+            results.push(self._open_box(owner_id.clone(), BoxPayment::Near(price)));
+        }
+
+        // Storage fee is deducted from paid price of opened boxes
+        let storage_fee = self._record_storage_usage(&owner_id, initial_storage_usage).min(paid);
+        self.treasury.storage_fees += storage_fee;
+        self._collect_box_payment(paid - storage_fee);
+
+        results
+    }
+
+    // Move unopened boxes to another account together with their paid price.
+    // Receiver must be registered with storage_deposit, as transfer doesn't pay for its storage
+    #[payable]
+    pub fn transfer_inventory(&mut self, receiver_id: AccountId, count: u32) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        let mut sender = self._get_user(&sender_id);
+        if count == 0 || count > sender.inventory_count || sender_id == receiver_id {
+            ContractError::WrongBoxesCount.panic();
+        }
+        if !self.token.accounts.contains_key(&receiver_id) {
+            ContractError::NotRegistered.panic();
+        }
+        if !self.campaign_finalized {
+            self._assert_can_receive_reserved_boxes(&receiver_id, count);
+        }
+
+        let paid = sender.inventory_paid * count as u128 / sender.inventory_count as u128;
+        sender.inventory_count -= count;
+        sender.inventory_paid -= paid;
+        self._save_user(&sender_id, &sender);
+
+        let mut receiver = self._get_user(&receiver_id);
+        receiver.inventory_count += count;
+        receiver.inventory_paid += paid;
+        self._save_user(&receiver_id, &receiver);
+
+        log!("Inventory transferred: {}, {}, {}", sender_id, receiver_id, count);
+    }
+
+    // Unopened boxes are paid back after campaign finalization
+    pub fn refund_inventory(&mut self) -> U128 {
+        if !self.campaign_finalized {
+            ContractError::CampaignNotOver.panic();
+        }
+
+        let owner_id = env::predecessor_account_id();
+        let mut user = self._get_user(&owner_id);
+        if user.inventory_count == 0 {
+            ContractError::WrongBoxesCount.panic();
+        }
+
+        let refund_amount = user.inventory_paid;
+        self.total_box_reserved -= user.inventory_count;
+        self.treasury.box_escrow -= refund_amount;
+        user.inventory_count = 0;
+        user.inventory_paid = 0;
+        self._save_user(&owner_id, &user);
+
+        log!("Inventory refunded: {}, {}", owner_id, refund_amount);
+        Promise::new(owner_id).transfer(refund_amount);
+
+        refund_amount.into()
+    }
+
+    pub fn get_inventory(&self, account_id: AccountId) -> InventoryInfo {
+        let user = self._get_user(&account_id);
+        InventoryInfo {
+            count: user.inventory_count,
+            paid: user.inventory_paid.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+    use near_sdk::test_utils::get_created_receipts;
+    use near_sdk::mock::VmAction;
+
+    // Deposit over boxes price that covers inventory storage
+    const STORAGE_DEPOSIT: Balance = ONE_TOKEN / 10;
+
+    fn buy(contract: &mut Contract, account_id: &AccountId, count: u32) {
+        testing_env!(context(account_id).attached_deposit(OPEN_BOX_PRICE * count as u128 + STORAGE_DEPOSIT).build());
+        contract.buy_boxes(count);
+    }
+
+    fn register(contract: &mut Contract, account_id: &AccountId) {
+        testing_env!(context(account_id).attached_deposit(contract.storage_balance_bounds().min.0).build());
+        contract.storage_deposit(None, None);
+    }
+
+    fn transfer(contract: &mut Contract, sender_id: &AccountId, receiver_id: &AccountId, count: u32) {
+        testing_env!(context(sender_id).attached_deposit(1).build());
+        contract.transfer_inventory(receiver_id.clone(), count);
+    }
+
+    #[test]
+    fn bought_boxes_are_reserved_from_supply() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let total_box_remain = contract.total_box_remain;
+
+        buy(&mut contract, &alice, 3);
+
+        let inventory = contract.get_inventory(alice);
+        assert_eq!((inventory.count, inventory.paid.0), (3, 3 * OPEN_BOX_PRICE));
+        assert_eq!(contract.total_box_remain, total_box_remain);
+        assert_eq!(contract._available_boxes(), total_box_remain - 3);
+        assert_eq!(contract.get_treasury().box_escrow.0, 3 * OPEN_BOX_PRICE);
+    }
+
+    #[test]
+    #[should_panic(expected = "[SOLD_OUT]")]
+    fn reserved_boxes_are_not_sold_again() {
+        let mut contract = setup_contract();
+        contract.total_box_remain = 2;
+        buy(&mut contract, &account("alice.near"), 2);
+
+        open_box(&mut contract, &account("bob.near"), None);
+    }
+
+    #[test]
+    fn open_from_inventory_collects_paid_price() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        buy(&mut contract, &alice, 3);
+        let total_collected = contract.get_treasury().total_collected.0;
+        let storage_used = contract._get_user(&alice).storage_used;
+
+        testing_env!(context(&alice).build());
+        assert_eq!(contract.open_from_inventory(2).len(), 2);
+
+        let storage_fee = (contract._get_user(&alice).storage_used - storage_used) as u128 * env::storage_byte_cost();
+        let inventory = contract.get_inventory(alice.clone());
+        assert_eq!((inventory.count, inventory.paid.0), (1, OPEN_BOX_PRICE));
+        assert_eq!(contract.get_user_rewards(alice).2, 2);
+        assert_eq!(contract.total_box_reserved, 1);
+        let treasury = contract.get_treasury();
+        assert_eq!(treasury.box_escrow.0, OPEN_BOX_PRICE);
+        assert_eq!(treasury.total_collected.0, total_collected + 2 * OPEN_BOX_PRICE - storage_fee);
+    }
+
+    #[test]
+    #[should_panic(expected = "[WRONG_BOXES_COUNT]")]
+    fn open_more_boxes_than_held() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        buy(&mut contract, &alice, 2);
+
+        testing_env!(context(&alice).build());
+        contract.open_from_inventory(3);
+    }
+
+    #[test]
+    fn transfer_inventory_moves_paid_price() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        let bob = account("bob.near");
+        buy(&mut contract, &alice, 3);
+        register(&mut contract, &bob);
+
+        transfer(&mut contract, &alice, &bob, 2);
+
+        let inventory = contract.get_inventory(alice);
+        assert_eq!((inventory.count, inventory.paid.0), (1, OPEN_BOX_PRICE));
+        let inventory = contract.get_inventory(bob.clone());
+        assert_eq!((inventory.count, inventory.paid.0), (2, 2 * OPEN_BOX_PRICE));
+
+        testing_env!(context(&bob).build());
+        contract.open_from_inventory(2);
+        assert_eq!(contract.get_user_rewards(bob).2, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOT_REGISTERED]")]
+    fn transfer_inventory_to_unregistered_account() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        buy(&mut contract, &alice, 2);
+
+        transfer(&mut contract, &alice, &account("bob.near"), 1);
+    }
+
+    #[test]
+    fn inventory_is_refunded_from_escrow() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        buy(&mut contract, &alice, 3);
+        let total_collected = contract.get_treasury().total_collected.0;
+        contract.campaign_finalized = true;
+
+        testing_env!(context(&alice).build());
+        assert_eq!(contract.refund_inventory().0, 3 * OPEN_BOX_PRICE);
+
+        let inventory = contract.get_inventory(alice.clone());
+        assert_eq!((inventory.count, inventory.paid.0), (0, 0));
+        assert_eq!(contract.total_box_reserved, 0);
+        let treasury = contract.get_treasury();
+        assert_eq!(treasury.box_escrow.0, 0);
+        assert_eq!(treasury.total_collected.0, total_collected);
+        let refund = get_created_receipts().into_iter().find(|receipt| receipt.receiver_id == alice);
+        assert!(matches!(
+            refund.unwrap().actions[..],
+            [VmAction::Transfer { deposit }] if deposit == 3 * OPEN_BOX_PRICE
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "[CAMPAIGN_NOT_OVER]")]
+    fn inventory_is_not_refunded_before_finalization() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        buy(&mut contract, &alice, 3);
+
+        testing_env!(context(&alice).build());
+        contract.refund_inventory();
+    }
+}
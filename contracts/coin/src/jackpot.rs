@@ -0,0 +1,246 @@
+use crate::*;
+use crate::staking::BPS_DENOMINATOR;
+use crate::events::{JackpotWonData, LolEvent};
+
+// Jackpot win chance is set in parts per million
+pub const JACKPOT_CHANCE_DENOMINATOR: u32 = 1_000_000;
+pub const MAX_JACKPOT_HISTORY: usize = 20;
+// Seed bytes used for jackpot draw, box reward draws use first bytes
+const JACKPOT_RANDOM_INDEX: usize = 8;
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct JackpotPool {
+    // Part of each box payment added to jackpot in basis points, 0 - jackpot is disabled
+    pub contribution_bps: u32,
+    // Chance to win jackpot on each opened box in parts per million
+    pub win_chance_ppm: u32,
+    pub amount: Balance,
+    pub total_contributed: Balance,
+    pub total_won: Balance,
+    pub history: Vec<JackpotWin>,
+}
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JackpotWin {
+    pub account_id: AccountId,
+    pub amount: U128,
+    pub timestamp: Timestamp,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JackpotInfo {
+    pub contribution_bps: u32,
+    pub win_chance_ppm: u32,
+    pub amount: U128,
+    pub total_contributed: U128,
+    pub total_won: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_jackpot(&self) -> JackpotInfo {
+        JackpotInfo {
+            contribution_bps: self.jackpot.contribution_bps,
+            win_chance_ppm: self.jackpot.win_chance_ppm,
+            amount: self.jackpot.amount.into(),
+            total_contributed: self.jackpot.total_contributed.into(),
+            total_won: self.jackpot.total_won.into(),
+        }
+    }
+
+    // Last jackpot winners, newest first
+    pub fn get_jackpot_history(&self) -> Vec<JackpotWin> {
+        self.jackpot.history.iter().rev().cloned().collect()
+    }
+
+    // -------------- Admin functions --------------
+
+    // Box payment slice in basis points and win chance in parts per million, contribution 0 - disable jackpot
+    pub fn set_jackpot_config(&mut self, contribution_bps: u32, win_chance_ppm: u32) {
+        self._assert_owner();
+        if contribution_bps as u128 + self.staking_pool.revenue_share_bps as u128 > BPS_DENOMINATOR {
+            ContractError::InvalidRevenueShare.panic();
+        }
+        if win_chance_ppm > JACKPOT_CHANCE_DENOMINATOR {
+            ContractError::InvalidJackpotChance.panic();
+        }
+
+        self.jackpot.contribution_bps = contribution_bps;
+        self.jackpot.win_chance_ppm = win_chance_ppm;
+        log!("Jackpot config: {}, {}", contribution_bps, win_chance_ppm);
+    }
+}
+
+impl Contract {
+    // Part of NEAR box payment, added before the draw of the box
+    pub(crate) fn _contribute_to_jackpot(&mut self, payment: Balance) {
+        let contribution = payment * self.jackpot.contribution_bps as u128 / BPS_DENOMINATOR;
+        self.jackpot.amount += contribution;
+        self.jackpot.total_contributed += contribution;
+    }
+
+    // Separate jackpot draw for every opened box, winner takes whole pool
+    pub(crate) fn _draw_jackpot(&mut self, owner_id: &AccountId) -> Balance {
+        if self.jackpot.amount == 0 || self.jackpot.win_chance_ppm == 0 {
+            return 0;
+        }
+        if self.random_u32(JACKPOT_RANDOM_INDEX) % JACKPOT_CHANCE_DENOMINATOR >= self.jackpot.win_chance_ppm {
+            return 0;
+        }
+
+        let amount = self.jackpot.amount;
+        self.jackpot.amount = 0;
+        self.jackpot.total_won += amount;
+        self.jackpot.history.push(JackpotWin {
+            account_id: owner_id.clone(),
+            amount: amount.into(),
+            timestamp: env::block_timestamp(),
+        });
+        if self.jackpot.history.len() > MAX_JACKPOT_HISTORY {
+            self.jackpot.history.remove(0);
+        }
+
+        self.treasury.total_paid_out += amount;
+        Promise::new(owner_id.clone()).transfer(amount);
+
+        LolEvent::JackpotWon(vec![JackpotWonData { account_id: owner_id, amount: amount.into() }]).emit();
+        amount
+    }
+
+    // Jackpot that was not won before campaign finalization becomes box revenue
+    pub(crate) fn _release_jackpot(&mut self) {
+        if self.jackpot.amount > 0 {
+            log!("Jackpot released: {}", self.jackpot.amount);
+            self.jackpot.amount = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+    use near_sdk::test_utils::get_logs;
+
+    fn setup_jackpot(contribution_bps: u32, win_chance_ppm: u32) -> Contract {
+        let mut contract = setup_contract();
+        contract.set_jackpot_config(contribution_bps, win_chance_ppm);
+        contract
+    }
+
+    // Draw value of the current box random seed, jackpot is won when it is below win chance
+    fn draw_value(contract: &Contract) -> u32 {
+        contract.random_u32(JACKPOT_RANDOM_INDEX) % JACKPOT_CHANCE_DENOMINATOR
+    }
+
+    #[test]
+    fn box_payment_is_contributed() {
+        let mut contract = setup_jackpot(500, 0);
+        let alice = account("alice.near");
+
+        open_box(&mut contract, &alice, None);
+        open_box(&mut contract, &alice, None);
+
+        let contribution = OPEN_BOX_PRICE * 500 / BPS_DENOMINATOR;
+        let jackpot = contract.get_jackpot();
+        assert_eq!(jackpot.amount.0, 2 * contribution);
+        assert_eq!(jackpot.total_contributed.0, 2 * contribution);
+        assert_eq!(jackpot.total_won.0, 0);
+    }
+
+    #[test]
+    fn box_contribution_is_drawn_by_the_same_box() {
+        let mut contract = setup_jackpot(500, JACKPOT_CHANCE_DENOMINATOR);
+        let alice = account("alice.near");
+
+        open_box(&mut contract, &alice, None);
+
+        let contribution = OPEN_BOX_PRICE * 500 / BPS_DENOMINATOR;
+        let jackpot = contract.get_jackpot();
+        assert_eq!(jackpot.amount.0, 0);
+        assert_eq!(jackpot.total_won.0, contribution);
+        assert!(get_logs().contains(&format!(
+            r#"EVENT_JSON:{{"standard":"lol","version":"1.0.0","event":"jackpot_won","data":[{{"account_id":"alice.near","amount":"{}"}}]}}"#,
+            contribution
+        )));
+    }
+
+    #[test]
+    fn draw_is_won_below_win_chance() {
+        let mut contract = setup_jackpot(500, 1);
+        let alice = account("alice.near");
+        testing_env!(context(&alice).build());
+        contract.jackpot.amount = ONE_TOKEN;
+
+        contract.jackpot.win_chance_ppm = draw_value(&contract);
+        assert_eq!(contract._draw_jackpot(&alice), 0);
+        assert_eq!(contract.jackpot.amount, ONE_TOKEN);
+
+        contract.jackpot.win_chance_ppm = draw_value(&contract) + 1;
+        assert_eq!(contract._draw_jackpot(&alice), ONE_TOKEN);
+        assert_eq!(contract.jackpot.amount, 0);
+    }
+
+    #[test]
+    fn empty_or_disabled_jackpot_is_not_drawn() {
+        let mut contract = setup_jackpot(500, JACKPOT_CHANCE_DENOMINATOR);
+        let alice = account("alice.near");
+        testing_env!(context(&alice).build());
+        assert_eq!(contract._draw_jackpot(&alice), 0);
+
+        contract.jackpot.amount = ONE_TOKEN;
+        contract.jackpot.win_chance_ppm = 0;
+        assert_eq!(contract._draw_jackpot(&alice), 0);
+        assert!(contract.get_jackpot_history().is_empty());
+    }
+
+    #[test]
+    fn jackpot_win_is_paid_out() {
+        let mut contract = setup_jackpot(500, JACKPOT_CHANCE_DENOMINATOR);
+        let alice = account("alice.near");
+        testing_env!(context(&alice).build());
+        contract.jackpot.amount = ONE_TOKEN;
+
+        contract._draw_jackpot(&alice);
+
+        assert_eq!(contract.get_treasury().total_paid_out.0, ONE_TOKEN);
+        assert_eq!(contract.get_jackpot().total_won.0, ONE_TOKEN);
+        let transfer = near_sdk::test_utils::get_created_receipts().into_iter().find(|receipt| receipt.receiver_id == alice);
+        assert!(matches!(
+            transfer.unwrap().actions[..],
+            [near_sdk::mock::VmAction::Transfer { deposit }] if deposit == ONE_TOKEN
+        ));
+    }
+
+    #[test]
+    fn jackpot_history_keeps_last_wins() {
+        let mut contract = setup_jackpot(500, JACKPOT_CHANCE_DENOMINATOR);
+        for index in 1..=MAX_JACKPOT_HISTORY + 2 {
+            let winner = account(&format!("winner{}.near", index));
+            testing_env!(context(&winner).build());
+            contract.jackpot.amount = index as u128 * ONE_TOKEN;
+            contract._draw_jackpot(&winner);
+        }
+
+        let history = contract.get_jackpot_history();
+        assert_eq!(history.len(), MAX_JACKPOT_HISTORY);
+        assert_eq!(history[0].account_id, account(&format!("winner{}.near", MAX_JACKPOT_HISTORY + 2)));
+        assert_eq!(history[0].amount.0, (MAX_JACKPOT_HISTORY as u128 + 2) * ONE_TOKEN);
+        assert_eq!(history[MAX_JACKPOT_HISTORY - 1].account_id, account("winner3.near"));
+    }
+
+    #[test]
+    fn released_jackpot_becomes_revenue() {
+        let mut contract = setup_jackpot(500, 0);
+        contract.jackpot.amount = 10 * ONE_TOKEN;
+        let available_revenue = contract.get_treasury().available_revenue.0;
+
+        contract._release_jackpot();
+
+        assert_eq!(contract.get_jackpot().amount.0, 0);
+        assert_eq!(contract.get_treasury().available_revenue.0, available_revenue + 10 * ONE_TOKEN);
+    }
+}
@@ -3,34 +3,110 @@ use near_contract_standards::fungible_token::metadata::{
     FungibleTokenMetadata, FungibleTokenMetadataProvider, FT_METADATA_SPEC,
 };
 use near_contract_standards::fungible_token::FungibleToken;
+use near_contract_standards::non_fungible_token::NonFungibleToken;
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LazyOption, LookupMap};
-use near_sdk::json_types::U128;
+use near_sdk::collections::{LazyOption, LookupMap, LookupSet};
+use near_sdk::json_types::{U128, U64};
 use std::convert::TryInto;
+use crate::achievements::UnlockedAchievement;
+use crate::config::{InitConfig, UnclaimedDestination};
+use crate::errors::ContractError;
+use crate::gift::GiftedBoxes;
+use crate::jackpot::JackpotPool;
+use crate::phase::{Phase, PhaseSettings, Phases};
+use crate::premium::PremiumGrant;
+use crate::rate_limit::{RateLimitConfig, RateLimitState};
+use crate::referral::{ReferralConfig, ReferralStats};
+use crate::staking::StakePosition;
+use crate::staking_rewards::{StakerRewards, StakingRewardPool};
+use crate::treasury::Treasury;
+use crate::user::UserState;
+use crate::verification::VerificationMode;
+use crate::vesting::VestingSchedule;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{env, log, near_bindgen, AccountId, Balance, PanicOnDefault, Promise, PromiseOrValue, BorshStorageKey, Timestamp};
 
+mod achievements;
+mod box_nft;
+mod burn;
+mod campaign;
+mod config;
+mod daily;
+mod errors;
+mod events;
+mod gift;
+mod inventory;
+mod jackpot;
+mod migration;
+mod phase;
+mod premium;
+mod rate_limit;
+mod receiver;
+mod referral;
+mod staking;
+mod staking_rewards;
+#[cfg(test)]
+mod test_utils;
+mod treasury;
+mod user;
 mod utils;
+mod verification;
+mod vesting;
+mod welcome;
 
 pub const ONE_TOKEN: u128 = 1_000_000_000_000_000_000_000_000;
 pub const TOTAL_SUPPLY_TOKENS_AMOUNT: u128 = 777_777_777 * ONE_TOKEN;
 pub const LP_TOKENS_AMOUNT: u128 = 327_736_777 * ONE_TOKEN;
 // 0.075 NEAR - open box price
 pub const OPEN_BOX_PRICE: Balance = 75 * ONE_TOKEN / 1000;
-pub const BOX_REWARDS: [f32; 5] = [0.0, 0.1, 1.0, 10.0, 1000.0];
+// 5000 LOL - open box price when paying with LOL tokens
+pub const LOL_OPEN_BOX_PRICE: Balance = 5000 * ONE_TOKEN;
+pub const MAX_BOXES_PER_CALL: u32 = 20;
+// NEAR prize for each reward type in yoctoNEAR: 0, 0.1, 1, 10 and 1000 NEAR
+pub const BOX_REWARDS: [Balance; 5] = [0, ONE_TOKEN / 10, ONE_TOKEN, 10 * ONE_TOKEN, 1000 * ONE_TOKEN];
 pub const PREMIUM_BOXES_PER_ACCOUNT: u32 = 100;
 pub const MINT_START_TIMESTAMP: Timestamp = 1704531600000000000; // 2024-01-06 09:00:00 UTC
+pub const ONE_DAY: Timestamp = 24 * 60 * 60 * 1_000_000_000;
+pub const LP_VESTING_CLIFF: Timestamp = 30 * ONE_DAY;
+pub const LP_VESTING_DURATION: Timestamp = 365 * ONE_DAY;
 
 
 #[derive(BorshStorageKey, BorshSerialize)]
 pub enum StorageKeys {
     Token,
     TokenMetadata,
+    // Per-user maps of the first deployment, users are moved to Users on their first update
     UserNearReward,
     UserLolReward,
     UserTotalBoxOpened,
     UserPremiumBoxOpened,
     UserAdditionalPremium,
+    // Keys are Borsh enum indexes, new keys must be appended at the end
+    Users,
+    UserBurned,
+    ReferralStats,
+    Allowlist,
+    VerifiedAccounts,
+    WelcomeRelayers,
+    GiftedBoxes,
+    Nft,
+    NftMetadata,
+    NftEnumeration,
+    NftApproval,
+    BoxNftPrice,
+    UserAchievements,
+    UserStakes,
+    UserStakingRewards,
+}
+
+// How opened box is paid. Badges are minted only for NEAR payments that charge storage of opened box,
+// referrers are rewarded only for paid boxes. NEAR payment of the box is contributed to jackpot before its draw
+#[derive(Clone, Copy, PartialEq)]
+pub enum BoxPayment {
+    Near(Balance),
+    Lol,
+    Free,
 }
 
 #[derive(Debug, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
@@ -44,13 +120,10 @@ pub struct LeaderboardItem {
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
     owner_id: AccountId,
+    config: InitConfig,
     token: FungibleToken,
     metadata: LazyOption<FungibleTokenMetadata>,
-    user_near_reward: LookupMap<AccountId, u128>,
-    user_lol_reward: LookupMap<AccountId, u128>,
-    user_total_box_opened: LookupMap<AccountId, u32>,
-    user_premium_box_opened: LookupMap<AccountId, u32>,
-    user_additional_premium: LookupMap<AccountId, u32>,
+    users: LookupMap<AccountId, UserState>,
     lol_tokens_remain: u128,
     rewards_remain: Vec<u32>,
     total_box_init: u32,
@@ -60,6 +133,41 @@ pub struct Contract {
     lol_leaderboard: Vec<LeaderboardItem>,
     last_participants: Vec<AccountId>,
     total_participants: u32,
+    lol_box_price: Balance,
+    user_burned: LookupMap<AccountId, u128>,
+    burn_leaderboard: Vec<LeaderboardItem>,
+    burned_total: u128,
+    lp_vesting: VestingSchedule,
+    campaign_finalized: bool,
+    treasury: Treasury,
+    referral_stats: LookupMap<AccountId, ReferralStats>,
+    referral_leaderboard: Vec<LeaderboardItem>,
+    referral_config: ReferralConfig,
+    phases: Phases,
+    allowlist: LookupSet<AccountId>,
+    rate_limit_config: RateLimitConfig,
+    global_rate_limit: RateLimitState,
+    verification_mode: VerificationMode,
+    verified_accounts: LookupMap<AccountId, Timestamp>,
+    welcome_relayers: LookupSet<AccountId>,
+    welcome_boxes_remain: u32,
+    welcome_boxes_opened: u32,
+    daily_lol_pool: u128,
+    // Paid but not opened boxes, included in total_box_remain
+    total_box_reserved: u32,
+    gifted_boxes: LookupMap<AccountId, Vec<GiftedBoxes>>,
+    // Box NFTs and soulbound achievement badges
+    nft: NonFungibleToken,
+    box_nft_price: LookupMap<String, u128>,
+    box_nft_next_id: u64,
+    user_achievements: LookupMap<AccountId, Vec<UnlockedAchievement>>,
+    user_stakes: LookupMap<AccountId, Vec<StakePosition>>,
+    total_staked: Balance,
+    staking_pool: StakingRewardPool,
+    user_staking_rewards: LookupMap<AccountId, StakerRewards>,
+    jackpot: JackpotPool,
+    // Boxes can't be bought or opened while paused, refunds are allowed
+    paused: bool,
 }
 
 const IMAGE_ICON: &str = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAMgAAADICAMAAACahl6sAAAAGXRFWHRTb2Z0d2FyZQBBZG9iZSBJbWFnZVJlYWR5ccllPAAAAyhpVFh0WE1MOmNvbS5hZG9iZS54bXAAAAAAADw/eHBhY2tldCBiZWdpbj0i77u/IiBpZD0iVzVNME1wQ2VoaUh6cmVTek5UY3prYzlkIj8+IDx4OnhtcG1ldGEgeG1sbnM6eD0iYWRvYmU6bnM6bWV0YS8iIHg6eG1wdGs9IkFkb2JlIFhNUCBDb3JlIDcuMi1jMDAwIDc5LjFiNjVhNzliNCwgMjAyMi8wNi8xMy0yMjowMTowMSAgICAgICAgIj4gPHJkZjpSREYgeG1sbnM6cmRmPSJodHRwOi8vd3d3LnczLm9yZy8xOTk5LzAyLzIyLXJkZi1zeW50YXgtbnMjIj4gPHJkZjpEZXNjcmlwdGlvbiByZGY6YWJvdXQ9IiIgeG1sbnM6eG1wPSJodHRwOi8vbnMuYWRvYmUuY29tL3hhcC8xLjAvIiB4bWxuczp4bXBNTT0iaHR0cDovL25zLmFkb2JlLmNvbS94YXAvMS4wL21tLyIgeG1sbnM6c3RSZWY9Imh0dHA6Ly9ucy5hZG9iZS5jb20veGFwLzEuMC9zVHlwZS9SZXNvdXJjZVJlZiMiIHhtcDpDcmVhdG9yVG9vbD0iQWRvYmUgUGhvdG9zaG9wIDIzLjUgKE1hY2ludG9zaCkiIHhtcE1NOkluc3RhbmNlSUQ9InhtcC5paWQ6QzQ4RjI0ODNBMzVGMTFFRTk0MTFFRkQ2NkQ0MzMwQkIiIHhtcE1NOkRvY3VtZW50SUQ9InhtcC5kaWQ6QzQ4RjI0ODRBMzVGMTFFRTk0MTFFRkQ2NkQ0MzMwQkIiPiA8eG1wTU06RGVyaXZlZEZyb20gc3RSZWY6aW5zdGFuY2VJRD0ieG1wLmlpZDpDNDhGMjQ4MUEzNUYxMUVFOTQxMUVGRDY2RDQzMzBCQiIgc3RSZWY6ZG9jdW1lbnRJRD0ieG1wLmRpZDpDNDhGMjQ4MkEzNUYxMUVFOTQxMUVGRDY2RDQzMzBCQiIvPiA8L3JkZjpEZXNjcmlwdGlvbj4gPC9yZGY6UkRGPiA8L3g6eG1wbWV0YT4gPD94cGFja2V0IGVuZD0iciI/PhJVqaYAAAMAUExURQAAAOXl5by8vA0NDcbGxsTExC0tLZCQkLCwsK6urszMzCYmJdfX193d3bi4uFpaWT4+PTY2NcLCwuHh4djY2MDAwJKSkgkJCQUFBaqqqhERER4eHTo6Oaampt7e3l5eXYyMjCopKSIiIaKioqCgoHd2dhoaGbq6uqioqIiIiJaWlrKyspiYmI6OjouKihUVFX18fHl4eFJSUmhoaJycnGJiYkRERGRkZJSUlM/Pz0xMTElJSIaGhk5OTlRUVEBAQH5+fqWlpIKCgsjIyEJCQmFgYG9ublBQUGxsbDAwMKysrP///2dmZnJycnR0dDMyMmtranBwcICAgEpKSoSEhFdWVgICAlxbW2BgX0dGRkZGRUVFRVxcWzU0Mzg3N3Bvb0BAPjw8PBAQEDExMS8vLxcXFx8fHtra2re3t7+/v5ubm3t7ewEBAfz8/Pn5+fv7+/Ly8vT09MvLy/Pz8+3t7eLi4vf39+fn5/39/bS0tOvr6/r6+vHx8QgICNLS0u7u7u/v79XV1fX19dTU1La2tv7+/rW1tZ+fn+zs7AcHB3p6evb29p6enunp6Q8PD/j4+NHR0fDw8NPT09DQ0Ojo6Orq6iAgHwwMDJqamuPj48rKyk9PTywsK+bm5nBwb4eHh4iIh76+vgEBANvb25eXl5aWlWlpaWNjY5+fnjMzM9TT00REQ3p5eTs7Ozg4OCcnJ4mJiWVlZRwcGxMTExQUFHJycUNDQ4aFhQIBAVZWVZuamp+enlRUU4+Pj8rKyW5ubdHR0Hx7e4uLi6urq+Dg3wMDA6Cfn0hHR5GRkQsLC319fSsqKp6encHBwV9fXyMjIn9/f5ycm8nJyUxLS52dncDAv6mpqQICASQkJFhYWHZ2dYODg6enp/Dw75WVlXt6ehkYGHt7enx8e3JxcaOjo5mZmWpqaWFhYWJiYcPDw1FRUVJSUU1NTU1MTNXU1IGBgaCgn0FBQQQEBEJCQUhIR21tbZOTk9PS0gIBAjIyMWloZ1VVVUtKSrOzswEAAP///95SPzwAAAEAdFJOU////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////////wBT9wclAAANsUlEQVR42tSdeUATVx7HHwmGSDgVnWmkiqh4trRYpZaqa9fWbaO2btt4IHYNiEcD4b4kHIJoUONtrNrSU0urokDtpeuumF0ia2Ft11VxXbbFurp0u2SFXdd203BoZjKTzLzJSzLz/ZP5veMT5s17v9+7gMU9Sh+8r2Lzmd8eDN7/XVx8/O3vpl+ee7hqzUTf4YVuKhAgz7FkpzTihf2hsmgDpsPNRqUaWKVWGs04jhnqmp//bm7VA/4F/AbJ+eDqlR9qVBiuBE70ZxzTN099ZoN/Ji9BNAGfxybIMSNgKaVWLxlxbdpWfoGkiV4Nqdf+F0BKrVMlfJSfxReQ9KBXJGIz4CijSXZ8YIn3QfIyKhMNSuCSlKaa00NSvQqy8f0lcjNAIKN4eWO210ACKmUYQCbdsBcueAXE9+8rzACpjPqTQSmeBjl/V68EyKUWL5ZqPAnie1evBm7R24apFR4D+efTKjdh9Ep8y9cjIFsq24zArVKr5ga6HSTld004cLuMyWfS3QsybboJeETaEJEbQTa9uUINPCXVNxvdBZIxWws8KDxxlVtANBFtauBZqcakoQc5exIDHhfeOQ01SMVII/CC1LXvIwXJe1MFvCTxG+noQIpvYMBrwn8YjgokrNMMvCilZAAakCEjlcC7ihqHAqQhCnhdqkbXQa6rAA8kPucqSIQe8ELYNxqXQFoNgCfSHlzrAkgLbzisJHNTOYM8xSMOK8nktRxBxvOKw0ryDDeQCXrAM2GHuYDMkgPeyXAMHmRsG+Ch9J/DghTJAC8VHQQHkhui5icIkBXBgGiWmnnKAdSzsyBAWjDAW+Gx7EEa9IDHMkWwBSmVAV6rXsQOJKdDyW8QkPA6K5DvtTznAOZYNiAiOeC9DBuYQYqPAAHoUhgjyGFcCCDGhUwgQnixel+u0c5B1p0AAtGBbKcgD2FCAcGfcQYSFgkEI/lqJyDfmoUDon5C4xBkiAoISKZ2hyAxaiGBgGUlDkCkYkFxAK0fPUjqEiAwNe2iBbloEBqI7mE6kLVTgeB0YBcNyECx8EC0p2hA9gMBKmE7BWS1Xogg2GgKyHGlEEHA4hQ7kIA2QXIAscgO5GNcmCDK42SQtFAgUNUGkkCkBqGCaBtJIIeUQgUBJzQEkMF73Ok4uBdE70sA+YrWwzVJbse8WM3ZR8GSgn85utzXf9vOfW8919g1tZaJqH5ZR0z8SNjwIH6OAPLp2zRDy72vrVdYtfu5mRwo2m40ZOcpSMrcNz7ciQcal1/cY5US6NcM6Zbk3AcZXkt9XJlqq8JjsCGJ8FmFCloVnXYQbpI32IzSD8K9Wzfvg4yiVvQUqfx8qF4mIUjhWLnzMNoXnWR0GOrdarkPcoPy9i61K74Voq/1S1E41Y4QaqKvyCbrocJrJ/L6QQpqKB7LDrvCs2rZ5lpdqmBSSov9D3dHY2cyFgakvqwfZCzFE5lJKTyWZabB6xQsZO/7tNobbK2B6RM/6wf5ntIC5lOKfoxdnq8o2GlAPSnZWIrBpzAd1Rf9ID9QHnVTMl7FKsvTCrbyJXZPyh2U5/OgPN6/9IL4ULv1xygZr2aT4WUFe+UTuhR8JeXxq7CduxUkyIAI5E4hBIgiAh2I7r1ekPHUTmINFxDMXwGlW8hA1Md7QYIBGpAqOA7F8GhUICA81QqSmYgGJHErJIjiFDKQtkArSEA9GpCBsByKTRJUIIYgK0i+CQnIcgW8vkIFgg+1gvjhSECucgBJlyECUXZZQbqUKEAupXMAUXyPCAR8aQWhC5XCgxzmwqEIwxCBSNJAYTUSkAxOIIoTiECi3gErI1GAJKVwA/FDBCK+CQbJUYAsoKnk2tyi1dJR1x8f2j1h87hVFwLT6FxfIxoQ7CK4akIBIrUz1/z8pQ4ZeaO4ru3o5Ktb7GFHogHBG8FDOgQg2FmScVq3xIGhatE2cr7BaEDMleCvOAKQJlITKXe2fsK8YBNlDOw6iPI4WGREADKHaHqdIRA3ldjllKMBAS+D/UoEIGOIvh9j6GgywbrUjAZkMYgHCECuESyfYG6ZOwnhmXo0IEfBMhQghI9WmI651Edt5qk1aECqgQQFyE2b4QY2fhAh41A0IAdAMwIQNeFducJmQLHdZj8VDYgMDEMAgofZDA+xiXr42OynowFpBlEIQLSBNkM2kfQ6Qgc/Aw3IHiQgGAHkSRalXiJMWdxFBYLi1dK9YzMMYlFqjF1QCAFIJIhEAGIssxluH8Zc6mZCxnGoGvtIFJ/fCwTLSsZCk0sI5svRgDSBBBQgxGj6bsYlt3uJUzpJaEASwUwUIO1E0wsrAOuBmSKtFg1IKOhQIwB5kmQbGO9semkDyXawCQ3ICbAQxTB+it0U4MTZDobAw87tspsnQTSMjwHzUThW1DBjWPfLEjnJQ8CGdc4PokS/RqMBUU8Bx1C4uiuKaQILebsDzktn7X3cb+iEzReDMs7Szi4uQANinA/+hXECETfN7Ih5cVlU/4/uq+CoEHYg6ujE+Dlz4p91tLsFjwAiPTyI7Ioot89Lz/RvvdTzl4c4cuTKWYAYj0Ts6+t61heIJtOujcHawQe1sCAh0lTik5zxWgC+5AgykTlApx1BngnzCaabV3gLZEngQDpFlGdvqUBdLjeQyUwg2ljqsypqhaNLgeU2DEgo7TKTfCUYxYkjc48jkK/7ojyXA+iSjaFUuCYLWGLVbEDKeqIdke159BWaB+I4gQx0OK1wrfe/7+AbspWyAvNDC7BU0ayhuk5N/DKo/TjTUYV85GAbF5AOqod5L8dmcKeB6QewfdRirSANNN/fRmradUXOZtFjwS84cPj3/4bKIuqzLaVrHSfMsVuqgo+3guyk+f62wlbpOSB+Bx5kxL3yoLuhueT6mqRWkBIaj6QLNuMAM5gLzeF7f5hXAZt0kl0QoLRnwcB0574oK+2uB8absIlsG1behU2ab+eNZPaAfE1t7UmwGfe4FaE5cGm6nc8TOdUQcltf2LuEg6a1i30gMy5ps6a6ApVkGmHxWScsiIjc1s/0goStYJ6BYlK2geIoMog0oqjbBVneg+TffXUviIZmseQhyIwH9A3dRKwTbH2RVN44yPJayHGyLX0r6OZRncT61+Eyfrgvmfw8S/vUGeTyYAcGZGf6H/1LAQeaGCIEzAq/909m906WxNmXFwRV3ErMvon0ggymianpBnD7GhqPsTAvo464JVCr78grHnuX+fcuYL5FF4Mczj7f4iZCwifOMvZmdBsFT2rYl3eeHGcITb8H4kfnt0eyXpWxjtxw5RFO1/5mdNJ7qx0b2Za3g+wLGhfcX1L+G1pXGHu1gFW+2yizd82Njiq13neGww0Lye2prMq7GG3nHf5o23bhIKJWN0Xqk+c8101DRtBt+Ki7UUENrKSURoQ6nbqWVQ4qZqAoGEcJjTZl2UDOOJzBNCTdvdLYLq1YRVV+w7uPTncce69f8vUD/q8V9gzG128qWCm6NqWJxfkF0eFftLw7bmI5TXk/Pniqq5P67hi/JWyE+U80cI8MtTJJ4rNNkfXuO4PBUE7cLCbIjbp9SkgjgmzGhMqBP0LavpcrEyqI3J+8xXWBUaAgMXZ7dQfohclhumq/DVygzb16uz3IAyYhcuiOUTbmrzsiRJDI4dQzHyYJ8AuMj6E5vCLrWeGBRJXSHfDipxUah/kj2pNqNgruXxJVRn8I0jWBtRL8ioPTnArDhfrJsj9f66qgTn7QVTk8KCxlupBOCnt+o+Mz6AbJhcNhaHd2vOErgjnn5ZMYp+c0Zh8QCsiKXzs/AnS0QNq77immQ1mDheFhzdzOBBIgiMMz5SLmg4v/KICXS/czNkdJf8H7c0DV8WlsQLJ5P3iszWB33Ho5z7tFw162B+C/yethMP4t65P8UxbyuJmo/13M/m6F3PD/8xZEtg3m2o4M3vYmcincRSpSnjZ4Qzfs1TaP8/JoU2wM/GVD53j46cIPaThc/9TFu/CQ+VYal3usUp/mmZdljCvgdrNYejCvSJSzfbje9ZZ2l0ckyl8N5n77Xtpl3pAYO7NduQ8xfZGOJ+08zse1GypTu3jxFcZjsly+/PQRHvSMWGwmgutou70+WjFUpiC5IFi6x7uRVPkEVFc2Zyzzon/yiawc3SXauy57rcnjHwagvNY85WMvXU0iPs3yrnnWF82vknghBKlOHoX6onmL5bWlHl9SoL29w4IexGJZk+zRI8DV0S/lWNwCYgk76cF/im62L0zdoEAseRtkHvoQK6OOrbO4D8TaUl5QeaJ3FF8ug6wYLIjFMmix298v7dGB0NWCB7GsnfW8W8f2eE1jpsUTIBZL4dAmt6HgyS25XOrECcRiKYhwDwqe/Lez3GrEEcRiyRp6B/n4SzuyNZtrfTiDWCyZDy7RI+wh1YYj7xVwr40LINah5KCDyYiieHjb0oocV+riEohVPpMWy12OtJj14WdWulgRV0Gsvf201uUqF1jM4upHB6S6XA3XQXp6Fv/xt9swDu1Fra0LOTdoE4o6IAHpUeCs/yXItRA+i1KnlyxaU6ZBVD4ykJ5Lq4tGvXEiWcxMY9SJo5Yf3JuRibBwlCC9LSbX9w+HPz0aqTdocaPd8PJtI64ziYdVz5k36U/ZGsQFowbpj0++vnPVhofnT7n7++WhiUkSSVJiQviSGU+/UfVZfkZ2jluK/EmAAQCCdsLVvt3l8wAAAABJRU5ErkJggg==";
@@ -79,6 +187,7 @@ impl Contract {
                 reference_hash: None,
                 decimals: 24,
             },
+            InitConfig::default_config(),
         )
     }
 
@@ -86,22 +195,25 @@ impl Contract {
     pub fn new(
         owner_id: AccountId,
         metadata: FungibleTokenMetadata,
+        config: InitConfig,
     ) -> Self {
-        assert!(!env::state_exists(), "Already initialized");
+        if env::state_exists() {
+            ContractError::AlreadyInitialized.panic();
+        }
         metadata.assert_valid();
+        config.assert_valid();
 
         let rewards_remain_init: Vec<u32> = vec![44449, 5000, 500, 50, 1];
-        let lol_tokens_for_boxes = TOTAL_SUPPLY_TOKENS_AMOUNT - LP_TOKENS_AMOUNT;
+        let lol_tokens_for_boxes: u128 = config.box_tokens_amount.into();
+        let lp_tokens_amount: u128 = config.lp_tokens_amount.into();
+        let lp_contract = config.liquidity_account_id();
 
         let mut this = Self {
             owner_id,
+            config: config.clone(),
             token: FungibleToken::new(StorageKeys::Token),
             metadata: LazyOption::new(StorageKeys::TokenMetadata, Some(&metadata)),
-            user_near_reward: LookupMap::new(StorageKeys::UserNearReward),
-            user_lol_reward: LookupMap::new(StorageKeys::UserLolReward),
-            user_total_box_opened: LookupMap::new(StorageKeys::UserTotalBoxOpened),
-            user_premium_box_opened: LookupMap::new(StorageKeys::UserPremiumBoxOpened),
-            user_additional_premium: LookupMap::new(StorageKeys::UserAdditionalPremium),
+            users: LookupMap::new(StorageKeys::Users),
             lol_tokens_remain: lol_tokens_for_boxes,
             rewards_remain: rewards_remain_init.clone(),
             total_box_init: rewards_remain_init.iter().sum(),
@@ -111,6 +223,57 @@ impl Contract {
             lol_leaderboard: vec![],
             last_participants: vec![],
             total_participants: 0,
+            lol_box_price: LOL_OPEN_BOX_PRICE,
+            user_burned: LookupMap::new(StorageKeys::UserBurned),
+            burn_leaderboard: vec![],
+            burned_total: 0,
+            lp_vesting: VestingSchedule::new(
+                lp_contract.clone(),
+                lp_tokens_amount,
+                config.lp_vesting_start_timestamp.unwrap_or_else(env::block_timestamp),
+                config.lp_vesting_cliff,
+                config.lp_vesting_duration,
+            ),
+            campaign_finalized: false,
+            treasury: Treasury::default(),
+            referral_stats: LookupMap::new(StorageKeys::ReferralStats),
+            referral_leaderboard: vec![],
+            referral_config: ReferralConfig::default(),
+            phases: Phases {
+                allowlist: None,
+                public: PhaseSettings {
+                    start_timestamp: config.mint_start_timestamp,
+                    price: config.open_box_price,
+                    account_limit: None,
+                },
+            },
+            allowlist: LookupSet::new(StorageKeys::Allowlist),
+            rate_limit_config: RateLimitConfig::default(),
+            global_rate_limit: RateLimitState::default(),
+            verification_mode: VerificationMode::Disabled,
+            verified_accounts: LookupMap::new(StorageKeys::VerifiedAccounts),
+            welcome_relayers: LookupSet::new(StorageKeys::WelcomeRelayers),
+            welcome_boxes_remain: 0,
+            welcome_boxes_opened: 0,
+            daily_lol_pool: 0,
+            total_box_reserved: 0,
+            gifted_boxes: LookupMap::new(StorageKeys::GiftedBoxes),
+            nft: NonFungibleToken::new(
+                StorageKeys::Nft,
+                env::current_account_id(),
+                Some(StorageKeys::NftMetadata),
+                Some(StorageKeys::NftEnumeration),
+                Some(StorageKeys::NftApproval),
+            ),
+            box_nft_price: LookupMap::new(StorageKeys::BoxNftPrice),
+            box_nft_next_id: 0,
+            user_achievements: LookupMap::new(StorageKeys::UserAchievements),
+            user_stakes: LookupMap::new(StorageKeys::UserStakes),
+            total_staked: 0,
+            staking_pool: StakingRewardPool::default(),
+            user_staking_rewards: LookupMap::new(StorageKeys::UserStakingRewards),
+            jackpot: JackpotPool::default(),
+            paused: false,
         };
 
         // Mint LOL tokens for box rewards
@@ -123,97 +286,67 @@ impl Contract {
             memo: Some("Initial tokens supply is minted"),
         }.emit();
 
-        // Mint LOL tokens for LP
-        let lp_contract: AccountId = format!("{}.{}", "liquidity", env::current_account_id()).try_into().unwrap();
+        // Mint LOL tokens for LP, locked on contract and released to LP by vesting schedule
         this.token.internal_register_account(&lp_contract);
-        this.token.internal_deposit(&lp_contract, LP_TOKENS_AMOUNT);
+        this.token.internal_deposit(&current_contract, lp_tokens_amount);
         near_contract_standards::fungible_token::events::FtMint {
-            owner_id: &lp_contract,
-            amount: &LP_TOKENS_AMOUNT.into(),
-            memo: Some("LP tokens supply is minted"),
+            owner_id: &current_contract,
+            amount: &lp_tokens_amount.into(),
+            memo: Some("LP tokens supply is minted for vesting"),
         }.emit();
 
-        // Register burn contract
-        let burn_contract: AccountId = format!("{}.{}", "burn", env::current_account_id()).try_into().unwrap();
-        this.token.internal_register_account(&burn_contract);
-
         this
     }
 
     fn on_account_closed(&mut self, account_id: AccountId, balance: Balance) {
         log!("Closed @{} with {}", account_id, balance);
-    }
 
-    #[payable]
-    pub fn open_box(&mut self) -> (usize, U128, U128) {
-        if env::attached_deposit() < OPEN_BOX_PRICE {
-            env::panic_str("Error: Wrong open deposit");
-        }
-        if self.total_box_remain == 0 {
-            env::panic_str("Error: No boxes remains");
-        }
-        if env::block_timestamp() < MINT_START_TIMESTAMP {
-            env::panic_str("Error: Too early to open boxes");
+        // Forced closure removes balance from total supply, count it as burned
+        if balance > 0 {
+            self.burned_total += balance;
+            near_contract_standards::fungible_token::events::FtBurn {
+                owner_id: &account_id,
+                amount: &balance.into(),
+                memo: Some("Account is closed"),
+            }.emit();
         }
 
-        let owner_id = env::predecessor_account_id();
-        let user_additional_premium: u32 = self.user_additional_premium.get(&owner_id).unwrap_or(0);
-        let user_premium_box_opened: u32 = self.user_premium_box_opened.get(&owner_id).unwrap_or(0);
-        let user_total_box_opened: u32 = self.user_total_box_opened.get(&owner_id).unwrap_or(0);
+        self._close_user(&account_id);
+    }
 
-        // Add Token Storage
-        if self.ft_balance_of(env::predecessor_account_id()) == U128(0) && user_total_box_opened == 0 {
-            self.token.internal_register_account(&owner_id);
+    #[payable]
+    pub fn open_box(&mut self, referrer: Option<AccountId>) -> (usize, U128, U128) {
+        let owner_id = env::predecessor_account_id();
+        if env::attached_deposit() < self._box_price(&owner_id) {
+            ContractError::WrongDeposit.panic();
         }
+        self._assert_can_open_boxes(&owner_id);
+        let initial_storage_usage = env::storage_usage();
 
-        let mut can_get_premium: bool = false;
-        if self.total_premium_remain > 0 && user_premium_box_opened < PREMIUM_BOXES_PER_ACCOUNT + user_additional_premium {
-            can_get_premium = true;
+        if let Some(referrer_id) = referrer {
+            self._set_referrer(&owner_id, &referrer_id);
         }
 
-        self.user_total_box_opened.remove(&owner_id);
-        self.user_total_box_opened.insert(&owner_id, &(user_total_box_opened + 1));
-        self.total_box_remain -= 1;
+        let result = self._open_box(owner_id.clone(), BoxPayment::Near(env::attached_deposit()));
 
-        let reward_type_index = self._get_random_user_reward(can_get_premium);
-        let is_premium_box = reward_type_index != 0;
-
-        self.rewards_remain[reward_type_index] -= 1;
-
-        // if owner_id not in self.last_participants - add to self.last_participants and increase self.total_participants
-        if !self.last_participants.contains(&owner_id) {
-            self.last_participants.push(owner_id.clone());
-            self.total_participants += 1;
-
-            // if self.total_participants > 500 - remove first element from self.last_participants
-            if self.total_participants > 500 {
-                self.last_participants.remove(0);
-            }
-        }
+        // Storage fee is deducted from box deposit
+        let storage_fee = self._record_storage_usage(&owner_id, initial_storage_usage).min(env::attached_deposit());
+        self.treasury.storage_fees += storage_fee;
+        self._collect_box_payment(env::attached_deposit() - storage_fee);
 
-        let lol_reward = self._claim_lol_reward(&owner_id, is_premium_box);
-        let mut near_reward = 0;
-        if is_premium_box {
-            near_reward = self._get_near_reward_amount(reward_type_index);
-            self._claim_near_reward(owner_id.clone(), near_reward);
-        }
-
-        env::log_str(&format!("Reward: {}, {}, {}, {}", owner_id, reward_type_index, lol_reward, near_reward));
-        (reward_type_index, lol_reward.into(), near_reward.into())
+        result
     }
 
-    pub fn get_user_rewards(&self, owner_id: AccountId) -> (u128, u128, u32) {
-        let user_near_reward = self.user_near_reward.get(&owner_id).unwrap_or(0);
-        let user_lol_reward = self.user_lol_reward.get(&owner_id).unwrap_or(0);
-        let total_box_opened = self.user_total_box_opened.get(&owner_id).unwrap_or(0);
+    pub fn get_user_rewards(&self, owner_id: AccountId) -> (U128, U128, u32) {
+        let user = self._get_user(&owner_id);
 
-        (user_lol_reward, user_near_reward, total_box_opened)
+        (user.lol_reward.into(), user.near_reward.into(), user.total_box_opened)
     }
 
-    pub fn get_total_stats(&self) -> (u32, Vec<u32>, u32, u32, Vec<u128>, Vec<f32>, u64) {
+    pub fn get_total_stats(&self) -> (u32, Vec<u32>, u32, u32, Vec<U128>, Vec<U128>, u64) {
         let remains = self.rewards_remain.clone();
         let total_box_remain = self.total_box_remain;
-        let lol_tokens_remain: Vec<u128> = vec![TOTAL_SUPPLY_TOKENS_AMOUNT, LP_TOKENS_AMOUNT, self.lol_tokens_remain];
+        let lol_tokens_remain: Vec<U128> = vec![self.config.total_supply, self.config.lp_tokens_amount, self.lol_tokens_remain.into()];
         let total_participants = self.total_participants;
         let total_box_init = self.total_box_init;
 
@@ -223,18 +356,18 @@ impl Contract {
             total_box_remain,
             total_box_init,
             lol_tokens_remain,
-            BOX_REWARDS.into(),
-            MINT_START_TIMESTAMP.into(),
+            BOX_REWARDS.iter().map(|amount| U128(*amount)).collect(),
+            self.phases.public.start_timestamp,
         )
     }
 
     pub fn get_all_participants(&self) -> Vec<LeaderboardItem> {
         let mut participants: Vec<LeaderboardItem> = vec![];
         self.last_participants.iter().for_each(|account_id| {
-            let user_lol_reward = self.user_lol_reward.get(&account_id).unwrap_or(0);
+            let user = self._get_user(account_id);
             participants.push(LeaderboardItem {
                 account_id: account_id.clone(),
-                amount: user_lol_reward.into(),
+                amount: user.lol_reward.into(),
             });
         });
 
@@ -245,32 +378,45 @@ impl Contract {
         (&self.near_leaderboard, &self.lol_leaderboard)
     }
 
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn get_lol_box_price(&self) -> U128 {
+        self.lol_box_price.into()
+    }
+
     pub fn user_premium_boxes_left(&self, account_id: AccountId) -> u32 {
-        let user_additional_premium: u32 = self.user_additional_premium.get(&account_id).unwrap_or(0);
-        let user_premium_box_opened: u32 = self.user_premium_box_opened.get(&account_id).unwrap_or(0);
-        PREMIUM_BOXES_PER_ACCOUNT + user_additional_premium - user_premium_box_opened
+        let user_additional_premium: u32 = self._user_additional_premium(&account_id);
+        let user_premium_box_opened: u32 = self._get_user(&account_id).premium_box_opened;
+        self.config.premium_boxes_per_account.saturating_add(user_additional_premium).saturating_sub(user_premium_box_opened)
     }
 
     // -------------- Admin functions --------------
 
     pub fn add_additional_premium(&mut self, account_id: AccountId, amount: u32) -> u32 {
-        if env::predecessor_account_id() != self.owner_id {
-            env::panic_str("Error: only owner can call this method");
-        }
+        self._assert_owner();
 
-        let user_additional_premium: u32 = self.user_additional_premium.get(&account_id).unwrap_or(0);
-        let new_amount: u32 = user_additional_premium + amount;
-        self.user_additional_premium.remove(&account_id);
-        self.user_additional_premium.insert(&account_id, &new_amount);
+        self._add_additional_premium(&account_id, amount, None)
+    }
 
-        new_amount
+    // Set open box price in LOL tokens, 0 - disable paying with LOL
+    pub fn set_lol_box_price(&mut self, price: U128) {
+        self._assert_owner();
+
+        self.lol_box_price = price.into();
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self._assert_owner();
+
+        self.paused = paused;
+        log!("Paused: {}", paused);
     }
 
     // DANGEROUS: Free NEAR tokens by storage cleanup
     pub fn cleanup_storage_erase_data(&mut self) {
-        if env::predecessor_account_id() != self.owner_id {
-            env::panic_str("Error: only owner can call this method");
-        }
+        self._assert_owner();
 
         self.last_participants = vec![];
         self.rewards_remain = vec![];
@@ -280,6 +426,144 @@ impl Contract {
 
     // -------------- Private functions --------------
 
+    pub(crate) fn _assert_owner(&self) {
+        if env::predecessor_account_id() != self.owner_id {
+            ContractError::Unauthorized.panic();
+        }
+    }
+
+    pub(crate) fn _assert_can_open_boxes(&self, account_id: &AccountId) {
+        if self.campaign_finalized {
+            ContractError::CampaignFinalized.panic();
+        }
+        self._assert_not_paused();
+        if self._available_boxes() == 0 {
+            ContractError::SoldOut.panic();
+        }
+        match self._current_phase() {
+            Phase::NotStarted => ContractError::NotStarted.panic(),
+            Phase::Allowlist if !self.allowlist.contains(account_id) => {
+                ContractError::NotAllowlisted.panic()
+            }
+            _ => {}
+        }
+        self._assert_rate_limit(account_id);
+        if self._account_boxes_left(account_id) == 0 {
+            ContractError::AccountLimitReached.panic();
+        }
+    }
+
+    pub(crate) fn _assert_not_paused(&self) {
+        if self.paused {
+            ContractError::Paused.panic();
+        }
+    }
+
+    // Boxes that can be bought, without reserved ones
+    pub(crate) fn _available_boxes(&self) -> u32 {
+        self.total_box_remain - self.total_box_reserved
+    }
+
+    // Boxes are reserved for account_id, the same phase checks as open_box are applied to it
+    pub(crate) fn _assert_can_reserve_boxes(&self, account_id: &AccountId, count: u32) {
+        if self.campaign_finalized {
+            ContractError::CampaignFinalized.panic();
+        }
+        self._assert_not_paused();
+        if self._available_boxes() < count {
+            ContractError::SoldOut.panic();
+        }
+        self._assert_can_receive_reserved_boxes(account_id, count);
+    }
+
+    // Reserved boxes count against account limit of the phase in which they are reserved
+    pub(crate) fn _assert_can_receive_reserved_boxes(&self, account_id: &AccountId, count: u32) {
+        match self._current_phase() {
+            Phase::NotStarted => ContractError::NotStarted.panic(),
+            Phase::Allowlist if !self.allowlist.contains(account_id) => {
+                ContractError::NotAllowlisted.panic()
+            }
+            _ => {}
+        }
+        if self._account_limit_left(account_id) < count {
+            ContractError::AccountLimitReached.panic();
+        }
+    }
+
+    // Reserved box is already paid, only campaign state and rate limits are checked
+    pub(crate) fn _assert_can_open_reserved_box(&self, account_id: &AccountId) {
+        if self.campaign_finalized {
+            ContractError::CampaignFinalized.panic();
+        }
+        self._assert_not_paused();
+        if self._current_phase() == Phase::NotStarted {
+            ContractError::NotStarted.panic();
+        }
+        self._assert_rate_limit(account_id);
+    }
+
+    fn _open_box(&mut self, owner_id: AccountId, payment: BoxPayment) -> (usize, U128, U128) {
+        let user_additional_premium: u32 = self._user_additional_premium(&owner_id);
+        let mut user = self._get_user(&owner_id);
+
+        // Add Token Storage, account can be already registered by storage_deposit
+        if !self.token.accounts.contains_key(&owner_id) {
+            self.token.internal_register_account(&owner_id);
+        }
+
+        let mut can_get_premium: bool = false;
+        if self.total_premium_remain > 0
+            && user.premium_box_opened < self.config.premium_boxes_per_account.saturating_add(user_additional_premium)
+            && self._is_verified(&owner_id) {
+            can_get_premium = true;
+        }
+
+        user.total_box_opened += 1;
+        let first_paid_box = payment != BoxPayment::Free && user.paid_boxes_opened == 0;
+        if payment != BoxPayment::Free {
+            user.paid_boxes_opened += 1;
+        }
+        self._save_user(&owner_id, &user);
+        self.total_box_remain -= 1;
+        self._record_rate_limit(&owner_id);
+
+        let reward_type_index = self._get_random_user_reward(can_get_premium);
+        let is_premium_box = reward_type_index != 0;
+
+        self.rewards_remain[reward_type_index] -= 1;
+
+        // if owner_id not in self.last_participants - add to self.last_participants and increase self.total_participants
+        if !self.last_participants.contains(&owner_id) {
+            self.last_participants.push(owner_id.clone());
+            self.total_participants += 1;
+
+            // if self.total_participants > 500 - remove first element from self.last_participants
+            if self.total_participants > 500 {
+                self.last_participants.remove(0);
+            }
+        }
+
+        let lol_reward = self._claim_lol_reward(&owner_id, is_premium_box);
+        if payment != BoxPayment::Free {
+            self._reward_referrer(&owner_id, lol_reward, first_paid_box);
+        }
+        let mut near_reward = 0;
+        if is_premium_box {
+            near_reward = self._get_near_reward_amount(reward_type_index);
+            self._claim_near_reward(owner_id.clone(), near_reward);
+        }
+        if let BoxPayment::Near(amount) = payment {
+            self._contribute_to_jackpot(amount);
+        }
+        let jackpot_amount = self._draw_jackpot(&owner_id);
+        if matches!(payment, BoxPayment::Near(_)) {
+            self._check_achievements(&owner_id, reward_type_index, jackpot_amount > 0);
+        }
+
+        env::log_str(&format!("Reward: {}, {}, {}, {}", owner_id, reward_type_index, lol_reward, near_reward));
+        (reward_type_index, lol_reward.into(), near_reward.into())
+    }
+
     fn _get_random_user_reward(&self, can_get_premium: bool) -> usize {
         let mut result: u32 = 0;
         let base_box_count: usize = (self.total_box_remain - self.rewards_remain[0] / 2) as usize;
@@ -301,54 +585,45 @@ impl Contract {
     }
 
     fn _get_near_reward_amount(&self, reward_type_index: usize) -> u128 {
-        match reward_type_index {
-            1 => { ONE_TOKEN / 10 }
-            2 => { ONE_TOKEN }
-            3 => { 10 * ONE_TOKEN }
-            4 => { 1000 * ONE_TOKEN }
-            _ => { 0 }
-        }
+        BOX_REWARDS.get(reward_type_index).copied().unwrap_or(0)
     }
 
     fn _claim_near_reward(&mut self, owner_id: AccountId, near_amount: u128) {
-        let user_premium_box_opened: u32 = self.user_premium_box_opened.get(&owner_id).unwrap_or(0);
-        self.user_premium_box_opened.remove(&owner_id);
-        self.user_premium_box_opened.insert(&owner_id, &(user_premium_box_opened + 1));
+        let mut user = self._get_user(&owner_id);
+        user.premium_box_opened += 1;
+        user.near_reward += near_amount;
+        self._save_user(&owner_id, &user);
         if self.total_premium_remain > 0 {
             self.total_premium_remain -= 1;
         }
 
-
-        let user_near_reward = self.user_near_reward.get(&owner_id).unwrap_or(0);
-        self.user_near_reward.remove(&owner_id);
-        self.user_near_reward.insert(&owner_id, &(user_near_reward + near_amount));
-
         // Update leaderboard
-        self._update_leaderboard("near_leaderboard", &owner_id, user_near_reward + near_amount);
+        self._update_leaderboard("near_leaderboard", &owner_id, user.near_reward);
 
         // Transfer tokens
+        self.treasury.total_paid_out += near_amount;
         Promise::new(owner_id).transfer(near_amount);
     }
 
     fn _claim_lol_reward(&mut self, owner_id: &AccountId, is_premium_box: bool) -> u128 {
         let min: usize = if is_premium_box { 100 } else { 1000 };
         let max: usize = if is_premium_box { 1000 } else { 10000 };
-        let lol_amount: u128 = (self.random_in_range(0, max - min) + min as u32) as u128 * ONE_TOKEN;
+        let lol_amount: u128 = self._boosted_lol_amount(owner_id, (self.random_in_range(0, max - min) + min as u32) as u128 * ONE_TOKEN);
 
-        let user_lol_reward = self.user_lol_reward.get(&owner_id).unwrap_or(0);
-        self.user_lol_reward.remove(&owner_id);
-        self.user_lol_reward.insert(&owner_id, &(user_lol_reward + lol_amount));
+        let mut user = self._get_user(owner_id);
+        user.lol_reward += lol_amount;
+        self._save_user(owner_id, &user);
 
         if self.lol_tokens_remain >= lol_amount {
             // Update leaderboard
-            self._update_leaderboard("lol_leaderboard", &owner_id, user_lol_reward + lol_amount);
+            self._update_leaderboard("lol_leaderboard", owner_id, user.lol_reward);
 
             // Transfer tokens
             self.lol_tokens_remain -= lol_amount;
-            self.token.internal_transfer(&env::current_account_id(), &owner_id, lol_amount.into(), None);
+            self.token.internal_transfer(&env::current_account_id(), owner_id, lol_amount, None);
         }
 
-        return lol_amount;
+        lol_amount
     }
 
     fn _update_leaderboard(&mut self, leaderboard_type: &str, owner_id: &AccountId, amount: u128) {
@@ -358,7 +633,9 @@ impl Contract {
         let leaderboard = match leaderboard_type {
             "near_leaderboard" => &mut self.near_leaderboard,
             "lol_leaderboard" => &mut self.lol_leaderboard,
-            _ => panic!("Error: wrong leaderboard type"),
+            "burn_leaderboard" => &mut self.burn_leaderboard,
+            "referral_leaderboard" => &mut self.referral_leaderboard,
+            _ => ContractError::WrongLeaderboard.panic(),
         };
 
         leaderboard.iter().for_each(|item| {
@@ -378,7 +655,7 @@ impl Contract {
             });
         }
 
-        leaderboard.sort_by(|a, b| b.amount.cmp(&a.amount));
+        leaderboard.sort_by_key(|item| std::cmp::Reverse(item.amount));
         if leaderboard.len() > 10 {
             leaderboard.pop();
         }
@@ -386,11 +663,47 @@ impl Contract {
 
     fn on_tokens_burned(&mut self, account_id: AccountId, amount: Balance) {
         log!("Account @{} burned {}", account_id, amount);
+        self.burned_total += amount;
     }
 }
 
 near_contract_standards::impl_fungible_token_core!(Contract, token, on_tokens_burned);
-near_contract_standards::impl_fungible_token_storage!(Contract, token, on_account_closed);
+// Same as impl_fungible_token_storage, but storage deposit is not refunded to accounts registered by free welcome box
+#[near_bindgen]
+impl StorageManagement for Contract {
+    #[payable]
+    fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>) -> StorageBalance {
+        self.token.storage_deposit(account_id, registration_only)
+    }
+
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        self.token.storage_withdraw(amount)
+    }
+
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        let closed = if self._get_user(&env::predecessor_account_id()).welcome_registered {
+            self._unregister_without_refund(force)
+        } else {
+            self.token.internal_storage_unregister(force)
+        };
+        if let Some((account_id, balance)) = closed {
+            self.on_account_closed(account_id, balance);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        self.token.storage_balance_bounds()
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.token.storage_balance_of(account_id)
+    }
+}
 
 #[near_bindgen]
 impl FungibleTokenMetadataProvider for Contract {
@@ -0,0 +1,218 @@
+use crate::*;
+
+// Contract state of the first deployment
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractV1 {
+    owner_id: AccountId,
+    token: FungibleToken,
+    metadata: LazyOption<FungibleTokenMetadata>,
+    // Per-user maps keep their storage prefixes, users are moved to UserState by _save_user
+    _user_near_reward: LookupMap<AccountId, u128>,
+    _user_lol_reward: LookupMap<AccountId, u128>,
+    _user_total_box_opened: LookupMap<AccountId, u32>,
+    _user_premium_box_opened: LookupMap<AccountId, u32>,
+    _user_additional_premium: LookupMap<AccountId, u32>,
+    lol_tokens_remain: u128,
+    rewards_remain: Vec<u32>,
+    total_box_init: u32,
+    total_box_remain: u32,
+    total_premium_remain: u32,
+    near_leaderboard: Vec<LeaderboardItem>,
+    lol_leaderboard: Vec<LeaderboardItem>,
+    last_participants: Vec<AccountId>,
+    total_participants: u32,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Upgrade state of the first deployment, new features get the same defaults as new_default_meta
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let old: ContractV1 = env::state_read().unwrap_or_else(|| ContractError::NotInitialized.panic());
+        // Mint has already started, so start timestamp of default config is not validated
+        let config = InitConfig {
+            mint_start_timestamp: MINT_START_TIMESTAMP,
+            ..InitConfig::default_config()
+        };
+        let lp_contract = config.liquidity_account_id();
+
+        let mut this = Self {
+            owner_id: old.owner_id,
+            config: config.clone(),
+            token: old.token,
+            metadata: old.metadata,
+            users: LookupMap::new(StorageKeys::Users),
+            lol_tokens_remain: old.lol_tokens_remain,
+            rewards_remain: old.rewards_remain,
+            total_box_init: old.total_box_init,
+            total_box_remain: old.total_box_remain,
+            total_premium_remain: old.total_premium_remain,
+            near_leaderboard: old.near_leaderboard,
+            lol_leaderboard: old.lol_leaderboard,
+            last_participants: old.last_participants,
+            total_participants: old.total_participants,
+            lol_box_price: LOL_OPEN_BOX_PRICE,
+            user_burned: LookupMap::new(StorageKeys::UserBurned),
+            burn_leaderboard: vec![],
+            burned_total: 0,
+            // LP tokens were minted straight to liquidity account, nothing is locked on contract
+            lp_vesting: VestingSchedule::new(
+                lp_contract,
+                0,
+                env::block_timestamp(),
+                config.lp_vesting_cliff,
+                config.lp_vesting_duration,
+            ),
+            campaign_finalized: false,
+            treasury: Treasury::default(),
+            referral_stats: LookupMap::new(StorageKeys::ReferralStats),
+            referral_leaderboard: vec![],
+            referral_config: ReferralConfig::default(),
+            phases: Phases {
+                allowlist: None,
+                public: PhaseSettings {
+                    start_timestamp: config.mint_start_timestamp,
+                    price: config.open_box_price,
+                    account_limit: None,
+                },
+            },
+            allowlist: LookupSet::new(StorageKeys::Allowlist),
+            rate_limit_config: RateLimitConfig::default(),
+            global_rate_limit: RateLimitState::default(),
+            verification_mode: VerificationMode::Disabled,
+            verified_accounts: LookupMap::new(StorageKeys::VerifiedAccounts),
+            welcome_relayers: LookupSet::new(StorageKeys::WelcomeRelayers),
+            welcome_boxes_remain: 0,
+            welcome_boxes_opened: 0,
+            daily_lol_pool: 0,
+            total_box_reserved: 0,
+            gifted_boxes: LookupMap::new(StorageKeys::GiftedBoxes),
+            nft: NonFungibleToken::new(
+                StorageKeys::Nft,
+                env::current_account_id(),
+                Some(StorageKeys::NftMetadata),
+                Some(StorageKeys::NftEnumeration),
+                Some(StorageKeys::NftApproval),
+            ),
+            box_nft_price: LookupMap::new(StorageKeys::BoxNftPrice),
+            box_nft_next_id: 0,
+            user_achievements: LookupMap::new(StorageKeys::UserAchievements),
+            user_stakes: LookupMap::new(StorageKeys::UserStakes),
+            total_staked: 0,
+            staking_pool: StakingRewardPool::default(),
+            user_staking_rewards: LookupMap::new(StorageKeys::UserStakingRewards),
+            jackpot: JackpotPool::default(),
+            paused: false,
+        };
+
+        // Tokens sent to burn account before real burn was implemented are burned, and the account
+        // is unregistered, so tokens can't be sent there instead of burn
+        let burn_account = this.config.burn_account_id();
+        if let Some(burn_account_balance) = this.token.accounts.get(&burn_account) {
+            if burn_account_balance > 0 {
+                this._internal_burn(&burn_account, burn_account_balance, "Burn account balance is burned");
+            }
+            this.token.accounts.remove(&burn_account);
+        }
+
+        log!("Contract state migrated");
+        this
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+
+    fn write_v1_state(user_id: &AccountId) {
+        testing_env!(context(&owner()).build());
+        let mut token = FungibleToken::new(StorageKeys::Token);
+        token.internal_register_account(&contract_account());
+        token.internal_deposit(&contract_account(), 1000 * ONE_TOKEN);
+        token.internal_register_account(user_id);
+        token.internal_deposit(user_id, 50 * ONE_TOKEN);
+        token.internal_register_account(&account("burn.lol.near"));
+        token.internal_deposit(&account("burn.lol.near"), 20 * ONE_TOKEN);
+
+        let mut user_lol_reward = LookupMap::new(StorageKeys::UserLolReward);
+        user_lol_reward.insert(user_id, &(50 * ONE_TOKEN));
+        let mut user_near_reward = LookupMap::new(StorageKeys::UserNearReward);
+        user_near_reward.insert(user_id, &ONE_TOKEN);
+        let mut user_total_box_opened = LookupMap::new(StorageKeys::UserTotalBoxOpened);
+        user_total_box_opened.insert(user_id, &3);
+        let mut user_premium_box_opened = LookupMap::new(StorageKeys::UserPremiumBoxOpened);
+        user_premium_box_opened.insert(user_id, &1);
+        let mut user_additional_premium = LookupMap::new(StorageKeys::UserAdditionalPremium);
+        user_additional_premium.insert(user_id, &5);
+
+        env::state_write(&ContractV1 {
+            owner_id: owner(),
+            token,
+            metadata: LazyOption::new(StorageKeys::TokenMetadata, None),
+            _user_near_reward: user_near_reward,
+            _user_lol_reward: user_lol_reward,
+            _user_total_box_opened: user_total_box_opened,
+            _user_premium_box_opened: user_premium_box_opened,
+            _user_additional_premium: user_additional_premium,
+            lol_tokens_remain: 1000 * ONE_TOKEN,
+            rewards_remain: vec![44446, 5000, 500, 50, 1],
+            total_box_init: 50000,
+            total_box_remain: 49997,
+            total_premium_remain: 5551,
+            near_leaderboard: vec![LeaderboardItem { account_id: user_id.clone(), amount: ONE_TOKEN.into() }],
+            lol_leaderboard: vec![LeaderboardItem { account_id: user_id.clone(), amount: (50 * ONE_TOKEN).into() }],
+            last_participants: vec![user_id.clone()],
+            total_participants: 1,
+        });
+    }
+
+    #[test]
+    fn migrate_keeps_v1_state_and_users() {
+        let user_id = account("alice.near");
+        write_v1_state(&user_id);
+
+        testing_env!(context(&contract_account()).build());
+        let contract = Contract::migrate();
+
+        assert_eq!(contract.ft_balance_of(user_id.clone()).0, 50 * ONE_TOKEN);
+        assert_eq!(contract.get_user_rewards(user_id.clone()), (U128(50 * ONE_TOKEN), U128(ONE_TOKEN), 3));
+        assert_eq!(contract.user_premium_boxes_left(user_id.clone()), PREMIUM_BOXES_PER_ACCOUNT + 5 - 1);
+        assert_eq!(contract.get_total_stats().2, 49997);
+        assert_eq!(contract.get_leaderboards().1[0].account_id, user_id);
+        assert_eq!(contract.get_lp_vesting().total_amount.0, 0);
+    }
+
+    #[test]
+    fn migrate_burns_burn_account_balance() {
+        let user_id = account("alice.near");
+        write_v1_state(&user_id);
+
+        testing_env!(context(&contract_account()).build());
+        let contract = Contract::migrate();
+
+        assert!(contract.storage_balance_of(account("burn.lol.near")).is_none());
+        assert_eq!(contract.get_burned_total().0, 20 * ONE_TOKEN);
+        assert_eq!(contract.ft_total_supply().0, 1050 * ONE_TOKEN);
+    }
+
+    #[test]
+    fn save_user_moves_v1_user_to_user_state() {
+        let user_id = account("alice.near");
+        write_v1_state(&user_id);
+        testing_env!(context(&contract_account()).build());
+        let mut contract = Contract::migrate();
+
+        let mut user = contract._get_user(&user_id);
+        user.total_box_opened += 1;
+        contract._save_user(&user_id, &user);
+
+        assert!(LookupMap::<AccountId, u32>::new(StorageKeys::UserTotalBoxOpened).get(&user_id).is_none());
+        assert!(LookupMap::<AccountId, u32>::new(StorageKeys::UserAdditionalPremium).get(&user_id).is_none());
+        assert_eq!(contract.get_user_rewards(user_id.clone()), (U128(50 * ONE_TOKEN), U128(ONE_TOKEN), 4));
+        assert_eq!(contract._get_user(&user_id).additional_premium, 5);
+        assert_eq!(contract._get_user(&user_id).paid_boxes_opened, 3);
+    }
+}
@@ -0,0 +1,273 @@
+use crate::*;
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PhaseSettings {
+    pub start_timestamp: Timestamp,
+    pub price: U128,
+    // Max boxes opened per account, None - unlimited
+    pub account_limit: Option<u32>,
+}
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Phases {
+    // Only allowlisted accounts can open boxes before public phase starts
+    pub allowlist: Option<PhaseSettings>,
+    pub public: PhaseSettings,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    NotStarted,
+    Allowlist,
+    Public,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PhaseInfo {
+    pub phase: Phase,
+    pub allowlist: Option<PhaseSettings>,
+    pub public: PhaseSettings,
+}
+
+impl Phases {
+    pub fn assert_valid(&self) {
+        if self.public.price.0 == 0 {
+            ContractError::ZeroPrice.panic();
+        }
+        if let Some(allowlist) = &self.allowlist {
+            if allowlist.price.0 == 0 {
+                ContractError::ZeroPrice.panic();
+            }
+            if allowlist.start_timestamp >= self.public.start_timestamp {
+                ContractError::InvalidPhases.panic();
+            }
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_phase(&self) -> PhaseInfo {
+        PhaseInfo {
+            phase: self._current_phase(),
+            allowlist: self.phases.allowlist.clone(),
+            public: self.phases.public.clone(),
+        }
+    }
+
+    pub fn is_allowlisted(&self, account_id: AccountId) -> bool {
+        self.allowlist.contains(&account_id)
+    }
+
+    // -------------- Admin functions --------------
+
+    pub fn set_phases(&mut self, phases: Phases) {
+        self._assert_owner();
+        phases.assert_valid();
+
+        self.phases = phases;
+    }
+
+    pub fn add_to_allowlist(&mut self, account_ids: Vec<AccountId>) {
+        self._assert_owner();
+
+        account_ids.iter().for_each(|account_id| {
+            self.allowlist.insert(account_id);
+        });
+    }
+
+    pub fn remove_from_allowlist(&mut self, account_ids: Vec<AccountId>) {
+        self._assert_owner();
+
+        account_ids.iter().for_each(|account_id| {
+            self.allowlist.remove(account_id);
+        });
+    }
+}
+
+impl Contract {
+    pub(crate) fn _current_phase(&self) -> Phase {
+        let now = env::block_timestamp();
+        if now >= self.phases.public.start_timestamp {
+            return Phase::Public;
+        }
+        match &self.phases.allowlist {
+            Some(allowlist) if now >= allowlist.start_timestamp => Phase::Allowlist,
+            _ => Phase::NotStarted,
+        }
+    }
+
+    pub(crate) fn _current_phase_settings(&self) -> &PhaseSettings {
+        match self._current_phase() {
+            Phase::Allowlist => self.phases.allowlist.as_ref().unwrap(),
+            _ => &self.phases.public,
+        }
+    }
+
+    // Boxes account can open in current phase, including rate limits
+    pub(crate) fn _account_boxes_left(&self, account_id: &AccountId) -> u32 {
+        let boxes_left = self._available_boxes().min(self._rate_limit_boxes_left(account_id));
+        self._account_limit_left(account_id).min(boxes_left)
+    }
+
+    // Boxes left by account limit of current phase, opened and reserved (inventory, gifted and box NFTs) boxes are counted
+    pub(crate) fn _account_limit_left(&self, account_id: &AccountId) -> u32 {
+        match self._current_phase_settings().account_limit {
+            Some(account_limit) => {
+                let user = self._get_user(account_id);
+                let gifted_count: u32 = self.gifted_boxes.get(account_id)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|gift| gift.count)
+                    .sum();
+                account_limit
+                    .saturating_sub(user.total_box_opened)
+                    .saturating_sub(user.inventory_count)
+                    .saturating_sub(gifted_count)
+                    .saturating_sub(user.box_nfts_bought)
+            }
+            None => u32::MAX,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+
+    const ALLOWLIST_PRICE: Balance = 2 * OPEN_BOX_PRICE;
+    const PUBLIC_START: Timestamp = MINT_START_TIMESTAMP + ONE_DAY;
+
+    fn phases() -> Phases {
+        Phases {
+            allowlist: Some(PhaseSettings {
+                start_timestamp: MINT_START_TIMESTAMP,
+                price: ALLOWLIST_PRICE.into(),
+                account_limit: Some(2),
+            }),
+            public: PhaseSettings {
+                start_timestamp: PUBLIC_START,
+                price: OPEN_BOX_PRICE.into(),
+                account_limit: None,
+            },
+        }
+    }
+
+    // Allowlist phase with alice.near allowlisted
+    fn setup_phases() -> Contract {
+        let mut contract = setup_contract();
+        contract.set_phases(phases());
+        contract.add_to_allowlist(vec![account("alice.near")]);
+        contract
+    }
+
+    fn open_box_at(contract: &mut Contract, account_id: &AccountId, timestamp: Timestamp, deposit: Balance) {
+        testing_env!(context(account_id).block_timestamp(timestamp).attached_deposit(deposit).build());
+        contract.open_box(None);
+    }
+
+    fn phase_at(contract: &Contract, timestamp: Timestamp) -> Phase {
+        testing_env!(context(&owner()).block_timestamp(timestamp).build());
+        contract.get_phase().phase
+    }
+
+    #[test]
+    fn phase_follows_start_timestamps() {
+        let contract = setup_phases();
+
+        assert!(phase_at(&contract, MINT_START_TIMESTAMP - 1) == Phase::NotStarted);
+        assert!(phase_at(&contract, MINT_START_TIMESTAMP) == Phase::Allowlist);
+        assert!(phase_at(&contract, PUBLIC_START - 1) == Phase::Allowlist);
+        assert!(phase_at(&contract, PUBLIC_START) == Phase::Public);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOT_STARTED]")]
+    fn open_box_before_allowlist_phase() {
+        let mut contract = setup_phases();
+
+        open_box_at(&mut contract, &account("alice.near"), MINT_START_TIMESTAMP - 1, ALLOWLIST_PRICE);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOT_ALLOWLISTED]")]
+    fn open_box_in_allowlist_phase_by_not_allowlisted() {
+        let mut contract = setup_phases();
+
+        open_box_at(&mut contract, &account("bob.near"), MINT_START_TIMESTAMP, ALLOWLIST_PRICE);
+    }
+
+    #[test]
+    #[should_panic(expected = "[WRONG_DEPOSIT]")]
+    fn open_box_in_allowlist_phase_at_public_price() {
+        let mut contract = setup_phases();
+
+        open_box_at(&mut contract, &account("alice.near"), MINT_START_TIMESTAMP, OPEN_BOX_PRICE);
+    }
+
+    #[test]
+    #[should_panic(expected = "[ACCOUNT_LIMIT_REACHED]")]
+    fn open_box_over_allowlist_account_limit() {
+        let mut contract = setup_phases();
+        let alice = account("alice.near");
+        open_box_at(&mut contract, &alice, MINT_START_TIMESTAMP, ALLOWLIST_PRICE);
+        open_box_at(&mut contract, &alice, MINT_START_TIMESTAMP + 1, ALLOWLIST_PRICE);
+
+        open_box_at(&mut contract, &alice, MINT_START_TIMESTAMP + 2, ALLOWLIST_PRICE);
+    }
+
+    #[test]
+    #[should_panic(expected = "[ACCOUNT_LIMIT_REACHED]")]
+    fn reserved_boxes_count_against_account_limit() {
+        let mut contract = setup_phases();
+        let alice = account("alice.near");
+        testing_env!(context(&alice).attached_deposit(2 * ALLOWLIST_PRICE + ONE_TOKEN / 10).build());
+        contract.buy_boxes(2);
+
+        open_box_at(&mut contract, &alice, MINT_START_TIMESTAMP + 1, ALLOWLIST_PRICE);
+    }
+
+    #[test]
+    fn public_phase_is_open_to_everyone_at_public_price() {
+        let mut contract = setup_phases();
+        let alice = account("alice.near");
+        let bob = account("bob.near");
+        open_box_at(&mut contract, &alice, MINT_START_TIMESTAMP, ALLOWLIST_PRICE);
+        open_box_at(&mut contract, &alice, MINT_START_TIMESTAMP + 1, ALLOWLIST_PRICE);
+
+        assert!(phase_at(&contract, PUBLIC_START) == Phase::Public);
+        assert_eq!(contract.get_box_price(bob.clone()).0, OPEN_BOX_PRICE);
+        open_box_at(&mut contract, &bob, PUBLIC_START, OPEN_BOX_PRICE);
+        open_box_at(&mut contract, &alice, PUBLIC_START + 1, OPEN_BOX_PRICE);
+
+        assert_eq!(contract.get_user_rewards(bob).2, 1);
+        assert_eq!(contract.get_user_rewards(alice).2, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "[INVALID_PHASES]")]
+    fn allowlist_phase_after_public_phase() {
+        let mut contract = setup_contract();
+        let mut phases = phases();
+        phases.allowlist.as_mut().unwrap().start_timestamp = PUBLIC_START;
+
+        contract.set_phases(phases);
+    }
+
+    #[test]
+    #[should_panic(expected = "[ZERO_PRICE]")]
+    fn allowlist_phase_with_zero_price() {
+        let mut contract = setup_contract();
+        let mut phases = phases();
+        phases.allowlist.as_mut().unwrap().price = U128(0);
+
+        contract.set_phases(phases);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::*;
+use crate::events::{LolEvent, PremiumGrantedData, PremiumRevokedData};
+
+pub const MAX_PREMIUM_GRANTS_PER_CALL: usize = 100;
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PremiumGrant {
+    pub amount: u32,
+    pub expires_at: Timestamp,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_premium_grants(&self, account_id: AccountId) -> Vec<PremiumGrant> {
+        self._active_premium_grants(&self._get_user(&account_id))
+    }
+
+    // -------------- Admin functions --------------
+
+    // Grant extra premium boxes to many accounts: (account_id, amount, expires_at), None - grant doesn't expire
+    pub fn add_additional_premium_bulk(&mut self, grants: Vec<(AccountId, u32, Option<Timestamp>)>) {
+        self._assert_owner();
+        if grants.is_empty() || grants.len() > MAX_PREMIUM_GRANTS_PER_CALL {
+            ContractError::WrongBatchSize.panic();
+        }
+
+        for (account_id, amount, expires_at) in grants {
+            self._add_additional_premium(&account_id, amount, expires_at);
+        }
+    }
+
+    // Revoke extra premium boxes from account, None - revoke all
+    pub fn revoke_additional_premium(&mut self, account_id: AccountId, amount: Option<u32>) -> u32 {
+        self._assert_owner();
+
+        let mut user = self._get_user(&account_id);
+        let mut grants = self._active_premium_grants(&user);
+        let mut to_revoke = amount.unwrap_or(u32::MAX);
+        let requested = to_revoke;
+
+        let revoked_permanent = user.additional_premium.min(to_revoke);
+        to_revoke -= revoked_permanent;
+        user.additional_premium -= revoked_permanent;
+
+        for grant in grants.iter_mut() {
+            let revoked_grant = grant.amount.min(to_revoke);
+            grant.amount -= revoked_grant;
+            to_revoke -= revoked_grant;
+        }
+        grants.retain(|grant| grant.amount > 0);
+        user.premium_grants = grants;
+        self._save_user(&account_id, &user);
+
+        let user_additional_premium = self._user_additional_premium(&account_id);
+        LolEvent::PremiumRevoked(vec![PremiumRevokedData {
+            account_id: &account_id,
+            amount: requested - to_revoke,
+            additional_premium: user_additional_premium,
+        }]).emit();
+        user_additional_premium
+    }
+}
+
+impl Contract {
+    // Permanent extra premium boxes plus not expired grants and staking premium
+    pub(crate) fn _user_additional_premium(&self, account_id: &AccountId) -> u32 {
+        let user = self._get_user(account_id);
+        self._active_premium_grants(&user)
+            .iter()
+            .fold(user.additional_premium, |total, grant| total.saturating_add(grant.amount))
+            .saturating_add(self._stake_extra_premium(account_id))
+    }
+
+    pub(crate) fn _add_additional_premium(&mut self, account_id: &AccountId, amount: u32, expires_at: Option<Timestamp>) -> u32 {
+        if amount == 0 {
+            ContractError::WrongAmount.panic();
+        }
+
+        let mut user = self._get_user(account_id);
+        match expires_at {
+            Some(expires_at) => {
+                if expires_at <= env::block_timestamp() {
+                    ContractError::GrantExpired.panic();
+                }
+                user.premium_grants = self._active_premium_grants(&user);
+                user.premium_grants.push(PremiumGrant { amount, expires_at });
+            }
+            None => {
+                user.additional_premium = user.additional_premium
+                    .checked_add(amount)
+                    .unwrap_or_else(|| ContractError::PremiumOverflow.panic());
+            }
+        }
+        self._save_user(account_id, &user);
+
+        let user_additional_premium = self._user_additional_premium(account_id);
+        LolEvent::PremiumGranted(vec![PremiumGrantedData {
+            account_id,
+            amount,
+            expires_at,
+            additional_premium: user_additional_premium,
+        }]).emit();
+        user_additional_premium
+    }
+
+    fn _active_premium_grants(&self, user: &UserState) -> Vec<PremiumGrant> {
+        let now = env::block_timestamp();
+        user.premium_grants
+            .iter()
+            .filter(|grant| grant.expires_at > now)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn bulk_grants_have_own_expiry() {
+        let mut contract = setup_contract();
+        let (alice, bob) = (account("alice.near"), account("bob.near"));
+        let expires_at = MINT_START_TIMESTAMP + ONE_DAY;
+
+        testing_env!(context(&owner()).build());
+        contract.add_additional_premium_bulk(vec![(alice.clone(), 5, Some(expires_at)), (bob.clone(), 3, None)]);
+
+        assert_eq!(contract._user_additional_premium(&alice), 5);
+        assert_eq!(contract._user_additional_premium(&bob), 3);
+        assert_eq!(
+            get_logs(),
+            vec![
+                format!(
+                    r#"EVENT_JSON:{{"standard":"lol","version":"1.0.0","event":"premium_granted","data":[{{"account_id":"alice.near","amount":5,"expires_at":{},"additional_premium":5}}]}}"#,
+                    expires_at
+                ),
+                r#"EVENT_JSON:{"standard":"lol","version":"1.0.0","event":"premium_granted","data":[{"account_id":"bob.near","amount":3,"expires_at":null,"additional_premium":3}]}"#.to_string(),
+            ]
+        );
+
+        testing_env!(context(&owner()).block_timestamp(expires_at).build());
+        assert_eq!(contract._user_additional_premium(&alice), 0);
+        assert_eq!(contract._user_additional_premium(&bob), 3);
+    }
+
+    #[test]
+    fn revoke_emits_revoked_amount() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        testing_env!(context(&owner()).build());
+        contract.add_additional_premium_bulk(vec![(alice.clone(), 2, None), (alice.clone(), 3, Some(MINT_START_TIMESTAMP + ONE_DAY))]);
+
+        testing_env!(context(&owner()).build());
+        assert_eq!(contract.revoke_additional_premium(alice.clone(), Some(4)), 1);
+        assert_eq!(
+            get_logs(),
+            vec![r#"EVENT_JSON:{"standard":"lol","version":"1.0.0","event":"premium_revoked","data":[{"account_id":"alice.near","amount":4,"additional_premium":1}]}"#]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "[GRANT_EXPIRED]")]
+    fn bulk_grant_already_expired() {
+        let mut contract = setup_contract();
+
+        testing_env!(context(&owner()).build());
+        contract.add_additional_premium_bulk(vec![(account("alice.near"), 1, Some(MINT_START_TIMESTAMP))]);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::*;
+
+#[derive(Clone, Default, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RateLimitConfig {
+    // None - no limit
+    pub account_per_block: Option<u32>,
+    pub account_per_day: Option<u32>,
+    pub global_per_block: Option<u32>,
+}
+
+// Boxes opened in current block and current day (UTC), window resets when block or day changes
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+pub struct RateLimitState {
+    pub block_height: u64,
+    pub block_count: u32,
+    pub day: u64,
+    pub day_count: u32,
+}
+
+impl RateLimitState {
+    fn current(&self) -> Self {
+        let block_height = env::block_height();
+        let day = env::block_timestamp() / ONE_DAY;
+        Self {
+            block_height,
+            block_count: if self.block_height == block_height { self.block_count } else { 0 },
+            day,
+            day_count: if self.day == day { self.day_count } else { 0 },
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_rate_limit_config(&self) -> &RateLimitConfig {
+        &self.rate_limit_config
+    }
+
+    // -------------- Admin functions --------------
+
+    pub fn set_rate_limit_config(&mut self, rate_limit_config: RateLimitConfig) {
+        self._assert_owner();
+
+        self.rate_limit_config = rate_limit_config;
+    }
+}
+
+impl Contract {
+    pub(crate) fn _assert_rate_limit(&self, account_id: &AccountId) {
+        let limits = &self.rate_limit_config;
+        let global = self.global_rate_limit.current();
+        let user = self._get_user(account_id).rate_limit.current();
+
+        if limits.global_per_block.map(|limit| global.block_count >= limit).unwrap_or(false) {
+            ContractError::GlobalBlockLimitReached.panic();
+        }
+        if limits.account_per_block.map(|limit| user.block_count >= limit).unwrap_or(false) {
+            ContractError::AccountBlockLimitReached.panic();
+        }
+        if limits.account_per_day.map(|limit| user.day_count >= limit).unwrap_or(false) {
+            ContractError::AccountDailyLimitReached.panic();
+        }
+    }
+
+    // Boxes account can open until any rate limit is reached
+    pub(crate) fn _rate_limit_boxes_left(&self, account_id: &AccountId) -> u32 {
+        let limits = &self.rate_limit_config;
+        let global = self.global_rate_limit.current();
+        let user = self._get_user(account_id).rate_limit.current();
+
+        [
+            limits.global_per_block.map(|limit| limit.saturating_sub(global.block_count)),
+            limits.account_per_block.map(|limit| limit.saturating_sub(user.block_count)),
+            limits.account_per_day.map(|limit| limit.saturating_sub(user.day_count)),
+        ]
+            .iter()
+            .flatten()
+            .fold(u32::MAX, |left, limit_left| left.min(*limit_left))
+    }
+
+    pub(crate) fn _record_rate_limit(&mut self, account_id: &AccountId) {
+        let mut global = self.global_rate_limit.current();
+        global.block_count += 1;
+        global.day_count += 1;
+        self.global_rate_limit = global;
+
+        let mut user = self._get_user(account_id);
+        user.rate_limit = user.rate_limit.current();
+        user.rate_limit.block_count += 1;
+        user.rate_limit.day_count += 1;
+        self._save_user(account_id, &user);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+
+    fn setup_limits(rate_limit_config: RateLimitConfig) -> Contract {
+        let mut contract = setup_contract();
+        contract.set_rate_limit_config(rate_limit_config);
+        contract
+    }
+
+    fn open_box_at(contract: &mut Contract, account_id: &AccountId, block_height: u64, block_timestamp: Timestamp) {
+        testing_env!(context(account_id)
+            .block_index(block_height)
+            .block_timestamp(block_timestamp)
+            .attached_deposit(OPEN_BOX_PRICE)
+            .build());
+        contract.open_box(None);
+    }
+
+    #[test]
+    fn global_block_limit_resets_in_next_block() {
+        let mut contract = setup_limits(RateLimitConfig { global_per_block: Some(2), ..Default::default() });
+
+        open_box_at(&mut contract, &account("alice.near"), 10, MINT_START_TIMESTAMP);
+        open_box_at(&mut contract, &account("bob.near"), 10, MINT_START_TIMESTAMP);
+        assert_eq!(contract._rate_limit_boxes_left(&account("carol.near")), 0);
+
+        open_box_at(&mut contract, &account("carol.near"), 11, MINT_START_TIMESTAMP + 1);
+        assert_eq!(contract._rate_limit_boxes_left(&account("carol.near")), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "[GLOBAL_BLOCK_LIMIT_REACHED]")]
+    fn global_block_limit_is_shared_by_accounts() {
+        let mut contract = setup_limits(RateLimitConfig { global_per_block: Some(2), ..Default::default() });
+
+        open_box_at(&mut contract, &account("alice.near"), 10, MINT_START_TIMESTAMP);
+        open_box_at(&mut contract, &account("bob.near"), 10, MINT_START_TIMESTAMP);
+        open_box_at(&mut contract, &account("carol.near"), 10, MINT_START_TIMESTAMP);
+    }
+
+    #[test]
+    fn account_block_limit_resets_in_next_block() {
+        let mut contract = setup_limits(RateLimitConfig { account_per_block: Some(1), ..Default::default() });
+        let alice = account("alice.near");
+
+        open_box_at(&mut contract, &alice, 10, MINT_START_TIMESTAMP);
+        assert_eq!(contract._rate_limit_boxes_left(&alice), 0);
+        // Other accounts are not limited by alice boxes
+        open_box_at(&mut contract, &account("bob.near"), 10, MINT_START_TIMESTAMP);
+
+        open_box_at(&mut contract, &alice, 11, MINT_START_TIMESTAMP + 1);
+        assert_eq!(contract._get_user(&alice).total_box_opened, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "[ACCOUNT_BLOCK_LIMIT_REACHED]")]
+    fn account_block_limit_in_same_block() {
+        let mut contract = setup_limits(RateLimitConfig { account_per_block: Some(1), ..Default::default() });
+        let alice = account("alice.near");
+
+        open_box_at(&mut contract, &alice, 10, MINT_START_TIMESTAMP);
+        open_box_at(&mut contract, &alice, 10, MINT_START_TIMESTAMP);
+    }
+
+    #[test]
+    fn account_daily_limit_resets_in_next_day() {
+        let mut contract = setup_limits(RateLimitConfig { account_per_day: Some(2), ..Default::default() });
+        let alice = account("alice.near");
+
+        open_box_at(&mut contract, &alice, 10, MINT_START_TIMESTAMP);
+        open_box_at(&mut contract, &alice, 11, MINT_START_TIMESTAMP + 1);
+        assert_eq!(contract._rate_limit_boxes_left(&alice), 0);
+
+        // Day window is UTC day of block timestamp, not 24 hours since first box
+        let next_day = (MINT_START_TIMESTAMP / ONE_DAY + 1) * ONE_DAY;
+        testing_env!(context(&alice).block_index(12).block_timestamp(next_day).build());
+        assert_eq!(contract._rate_limit_boxes_left(&alice), 2);
+
+        open_box_at(&mut contract, &alice, 12, next_day);
+        assert_eq!(contract._rate_limit_boxes_left(&alice), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "[ACCOUNT_DAILY_LIMIT_REACHED]")]
+    fn account_daily_limit_in_next_blocks() {
+        let mut contract = setup_limits(RateLimitConfig { account_per_day: Some(2), ..Default::default() });
+        let alice = account("alice.near");
+
+        open_box_at(&mut contract, &alice, 10, MINT_START_TIMESTAMP);
+        open_box_at(&mut contract, &alice, 11, MINT_START_TIMESTAMP + 1);
+        open_box_at(&mut contract, &alice, 12, MINT_START_TIMESTAMP + 2);
+    }
+}
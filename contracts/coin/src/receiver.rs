@@ -0,0 +1,173 @@
+use crate::*;
+use crate::staking::StakeBenefit;
+use crate::utils::mul_div;
+use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum FtTransferMessage {
+    OpenBoxes(u32),
+    Burn,
+    Stake { duration_days: u32, benefit: StakeBenefit },
+}
+
+#[near_bindgen]
+impl FungibleTokenReceiver for Contract {
+    // Pay for boxes with LOL tokens: ft_transfer_call with msg {"open_boxes": n}
+    // Storage of opened boxes is paid with LOL too, unused tokens are returned to the sender by ft_resolve_transfer
+    // Burn received tokens: ft_transfer_call with msg "burn"
+    // Stake tokens: ft_transfer_call with msg {"stake": {"duration_days": 90, "benefit": "discount"}}
+    fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        if env::predecessor_account_id() != env::current_account_id() {
+            ContractError::WrongToken.panic();
+        }
+
+        let message: FtTransferMessage = near_sdk::serde_json::from_str(&msg)
+            .unwrap_or_else(|_| ContractError::WrongMessage.panic());
+
+        match message {
+            FtTransferMessage::OpenBoxes(count) => self._open_boxes_for_lol(sender_id, amount.into(), count),
+            FtTransferMessage::Burn => {
+                self._internal_burn(&env::current_account_id(), amount.into(), "Tokens burned");
+                self._add_user_burned(&sender_id, amount.into());
+                PromiseOrValue::Value(U128(0))
+            }
+            FtTransferMessage::Stake { duration_days, benefit } => self._stake(sender_id, amount.into(), duration_days, benefit),
+        }
+    }
+}
+
+impl Contract {
+    fn _open_boxes_for_lol(&mut self, sender_id: AccountId, amount: Balance, count: u32) -> PromiseOrValue<U128> {
+        if self.lol_box_price == 0 {
+            ContractError::LolPaymentDisabled.panic();
+        }
+        if count == 0 || count > MAX_BOXES_PER_CALL {
+            ContractError::WrongBoxesCount.panic();
+        }
+        if amount < self.lol_box_price * count as u128 {
+            ContractError::WrongDeposit.panic();
+        }
+        self._assert_can_open_boxes(&sender_id);
+
+        let initial_storage_usage = env::storage_usage();
+        let mut opened: u32 = 0;
+        while opened < count && self._account_boxes_left(&sender_id) > 0 {
+            // Boxes paid with LOL don't pay NEAR for storage, so achievement badges are not minted
+            self._open_box(sender_id.clone(), BoxPayment::Lol);
+            opened += 1;
+        }
+        // Storage cost is converted to LOL with the rate of box prices in LOL and NEAR
+        let storage_cost = self._record_storage_usage(&sender_id, initial_storage_usage);
+        let storage_fee = mul_div(storage_cost, self.lol_box_price, self.config.open_box_price.0);
+        let spent = self.lol_box_price * opened as u128 + storage_fee;
+        if amount < spent {
+            ContractError::WrongDeposit.panic();
+        }
+
+        // Spent LOL tokens are recycled back into the box rewards allocation
+        self.lol_tokens_remain += spent;
+        log!("Boxes paid with LOL: {}, {}, {}", sender_id, opened, spent);
+
+        PromiseOrValue::Value(U128(amount - spent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+    use near_sdk::test_utils::get_logs;
+
+    const LOL_BOX_PRICE: Balance = 5000 * ONE_TOKEN;
+
+    // Account with LOL balance and LOL box payments enabled
+    fn setup_lol_payer() -> (Contract, AccountId) {
+        let mut contract = setup_contract();
+        contract.set_lol_box_price(LOL_BOX_PRICE.into());
+        let alice = account("alice.near");
+        contract.token.internal_register_account(&alice);
+        contract.token.internal_transfer(&contract_account(), &alice, 100 * LOL_BOX_PRICE, None);
+        (contract, alice)
+    }
+
+    // ft_transfer_call of account to contract, returns unused amount
+    fn transfer_call(contract: &mut Contract, account_id: &AccountId, amount: Balance, msg: &str) -> Balance {
+        testing_env!(context(&contract_account()).build());
+        contract.token.internal_transfer(account_id, &contract_account(), amount, None);
+        match contract.ft_on_transfer(account_id.clone(), amount.into(), msg.to_string()) {
+            PromiseOrValue::Value(unused) => unused.0,
+            PromiseOrValue::Promise(_) => panic!("Unexpected promise"),
+        }
+    }
+
+    // Storage fee in LOL of boxes opened by account
+    fn storage_fee(contract: &Contract, account_id: &AccountId) -> Balance {
+        let storage_cost = contract._get_user(account_id).storage_used as u128 * env::storage_byte_cost();
+        mul_div(storage_cost, LOL_BOX_PRICE, OPEN_BOX_PRICE)
+    }
+
+    #[test]
+    fn open_boxes_paid_with_lol() {
+        let (mut contract, alice) = setup_lol_payer();
+
+        let unused = transfer_call(&mut contract, &alice, 3 * LOL_BOX_PRICE, r#"{"open_boxes":2}"#);
+
+        let storage_fee = storage_fee(&contract, &alice);
+        assert!(storage_fee > 0);
+        assert_eq!(unused, LOL_BOX_PRICE - storage_fee);
+        assert_eq!(contract.get_user_rewards(alice.clone()).2, 2);
+        assert!(get_logs().contains(&format!("Boxes paid with LOL: alice.near, 2, {}", 2 * LOL_BOX_PRICE + storage_fee)));
+    }
+
+    #[test]
+    fn unused_amount_of_not_opened_boxes_is_returned() {
+        let (mut contract, alice) = setup_lol_payer();
+        contract.set_rate_limit_config(RateLimitConfig { account_per_block: Some(1), ..Default::default() });
+
+        let unused = transfer_call(&mut contract, &alice, 4 * LOL_BOX_PRICE, r#"{"open_boxes":3}"#);
+
+        assert_eq!(contract.get_user_rewards(alice.clone()).2, 1);
+        assert_eq!(unused, 3 * LOL_BOX_PRICE - storage_fee(&contract, &alice));
+    }
+
+    #[test]
+    #[should_panic(expected = "[WRONG_DEPOSIT]")]
+    fn open_boxes_without_lol_for_storage() {
+        let (mut contract, alice) = setup_lol_payer();
+
+        transfer_call(&mut contract, &alice, 2 * LOL_BOX_PRICE, r#"{"open_boxes":2}"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "[WRONG_MESSAGE]")]
+    fn malformed_message() {
+        let (mut contract, alice) = setup_lol_payer();
+
+        transfer_call(&mut contract, &alice, LOL_BOX_PRICE, r#"{"open_boxes":"two"}"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "[WRONG_TOKEN]")]
+    fn transfer_of_other_token() {
+        let (mut contract, alice) = setup_lol_payer();
+        testing_env!(context(&account("usdt.near")).build());
+
+        contract.ft_on_transfer(alice, LOL_BOX_PRICE.into(), r#""burn""#.to_string());
+    }
+
+    #[test]
+    fn burn_message_burns_tokens() {
+        let (mut contract, alice) = setup_lol_payer();
+        let total_supply = contract.ft_total_supply().0;
+
+        let unused = transfer_call(&mut contract, &alice, LOL_BOX_PRICE, r#""burn""#);
+
+        assert_eq!(unused, 0);
+        assert_eq!(contract.ft_total_supply().0, total_supply - LOL_BOX_PRICE);
+        assert_eq!(contract.get_user_burned(alice.clone()).0, LOL_BOX_PRICE);
+        assert_eq!(contract.ft_balance_of(alice).0, 99 * LOL_BOX_PRICE);
+    }
+}
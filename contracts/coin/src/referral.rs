@@ -0,0 +1,242 @@
+use crate::*;
+
+pub const MAX_REFERRAL_DEPTH: u32 = 20;
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReferralConfig {
+    // Referrer bonus in basis points of LOL reward from each referral box
+    pub lol_bonus_bps: u32,
+    // Referrer gets one extra premium box slot for every N referrals that opened a paid box, 0 - disabled
+    pub paid_referrals_per_premium_slot: u32,
+}
+
+impl Default for ReferralConfig {
+    fn default() -> Self {
+        Self {
+            lol_bonus_bps: 1000,
+            paid_referrals_per_premium_slot: 10,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReferralStats {
+    pub referrals_count: u32,
+    // Referrals that opened a paid box while referrer was eligible for rewards
+    pub paid_referrals: u32,
+    // Paid boxes opened by referrals
+    pub boxes_opened: u32,
+    pub lol_earned: U128,
+    pub premium_earned: u32,
+}
+
+impl Default for ReferralStats {
+    fn default() -> Self {
+        Self {
+            referrals_count: 0,
+            paid_referrals: 0,
+            boxes_opened: 0,
+            lol_earned: U128(0),
+            premium_earned: 0,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_referrer(&self, account_id: AccountId) -> Option<AccountId> {
+        self._get_user(&account_id).referrer
+    }
+
+    pub fn get_referral_stats(&self, account_id: AccountId) -> ReferralStats {
+        self.referral_stats.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn get_referral_leaderboard(&self) -> &Vec<LeaderboardItem> {
+        &self.referral_leaderboard
+    }
+
+    pub fn get_referral_config(&self) -> &ReferralConfig {
+        &self.referral_config
+    }
+
+    // -------------- Admin functions --------------
+
+    pub fn set_referral_config(&mut self, referral_config: ReferralConfig) {
+        self._assert_owner();
+        if referral_config.lol_bonus_bps > 10000 {
+            ContractError::InvalidReferralConfig.panic();
+        }
+
+        self.referral_config = referral_config;
+    }
+}
+
+impl Contract {
+    // Record first referrer for account, self-referral and cycles are rejected
+    pub(crate) fn _set_referrer(&mut self, account_id: &AccountId, referrer_id: &AccountId) {
+        let mut user = self._get_user(account_id);
+        if user.referrer.is_some() {
+            return;
+        }
+        if account_id == referrer_id {
+            ContractError::SelfReferral.panic();
+        }
+
+        let mut depth: u32 = 0;
+        let mut next_referrer = self._get_user(referrer_id).referrer;
+        while let Some(upper_referrer) = next_referrer {
+            if &upper_referrer == account_id {
+                ContractError::ReferralCycle.panic();
+            }
+            if depth >= MAX_REFERRAL_DEPTH {
+                ContractError::ReferralChainTooDeep.panic();
+            }
+            next_referrer = self._get_user(&upper_referrer).referrer;
+            depth += 1;
+        }
+
+        user.referrer = Some(referrer_id.clone());
+        self._save_user(account_id, &user);
+
+        let mut stats = self.referral_stats.get(referrer_id).unwrap_or_default();
+        stats.referrals_count += 1;
+        self.referral_stats.insert(referrer_id, &stats);
+
+        // Update leaderboard
+        self._update_leaderboard("referral_leaderboard", referrer_id, stats.referrals_count as u128);
+        log!("Referral: {}, {}", account_id, referrer_id);
+    }
+
+    // Reward referrer for paid box opened by referral. Only registered referrers with own paid box are
+    // rewarded, so rewards can't be farmed with fresh accounts that don't pay for storage or boxes
+    pub(crate) fn _reward_referrer(&mut self, account_id: &AccountId, lol_reward: Balance, first_paid_box: bool) {
+        let referrer_id = match self._get_user(account_id).referrer {
+            Some(referrer_id) => referrer_id,
+            None => return,
+        };
+        if !self.token.accounts.contains_key(&referrer_id) || self._get_user(&referrer_id).paid_boxes_opened == 0 {
+            return;
+        }
+
+        let mut stats = self.referral_stats.get(&referrer_id).unwrap_or_default();
+        stats.boxes_opened += 1;
+        if first_paid_box {
+            stats.paid_referrals += 1;
+        }
+
+        let lol_bonus = lol_reward * self.referral_config.lol_bonus_bps as u128 / 10000;
+        if lol_bonus > 0 && self.lol_tokens_remain >= lol_bonus {
+            self.lol_tokens_remain -= lol_bonus;
+            self.token.internal_transfer(&env::current_account_id(), &referrer_id, lol_bonus, None);
+            stats.lol_earned = (stats.lol_earned.0 + lol_bonus).into();
+        }
+
+        let paid_referrals_per_premium_slot = self.referral_config.paid_referrals_per_premium_slot;
+        if paid_referrals_per_premium_slot > 0 && stats.paid_referrals / paid_referrals_per_premium_slot > stats.premium_earned {
+            self._add_additional_premium(&referrer_id, 1, None);
+            stats.premium_earned += 1;
+        }
+
+        self.referral_stats.insert(&referrer_id, &stats);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+
+    fn setup_referrals(paid_referrals_per_premium_slot: u32) -> Contract {
+        let mut contract = setup_contract();
+        contract.set_referral_config(ReferralConfig { lol_bonus_bps: 1000, paid_referrals_per_premium_slot });
+        contract
+    }
+
+    #[test]
+    fn unregistered_referrer_is_not_rewarded() {
+        let mut contract = setup_referrals(1);
+        let (alice, bob) = (account("alice.near"), account("bob.near"));
+
+        open_box(&mut contract, &bob, Some(&alice));
+
+        assert!(!contract.token.accounts.contains_key(&alice));
+        let stats = contract.get_referral_stats(alice.clone());
+        assert_eq!((stats.referrals_count, stats.paid_referrals, stats.boxes_opened, stats.lol_earned.0), (1, 0, 0, 0));
+        assert_eq!(contract.user_premium_boxes_left(alice), PREMIUM_BOXES_PER_ACCOUNT);
+    }
+
+    #[test]
+    fn referrer_without_paid_box_is_not_rewarded() {
+        let mut contract = setup_referrals(1);
+        let (alice, bob) = (account("alice.near"), account("bob.near"));
+        testing_env!(context(&alice).attached_deposit(contract.storage_balance_bounds().min.0).build());
+        contract.storage_deposit(None, None);
+
+        open_box(&mut contract, &bob, Some(&alice));
+
+        assert_eq!(contract.get_referral_stats(alice.clone()).boxes_opened, 0);
+        assert_eq!(contract.ft_balance_of(alice).0, 0);
+    }
+
+    #[test]
+    fn paid_referrer_is_rewarded_for_paid_boxes() {
+        let mut contract = setup_referrals(1);
+        let (alice, bob) = (account("alice.near"), account("bob.near"));
+        open_box(&mut contract, &alice, None);
+        let alice_balance = contract.ft_balance_of(alice.clone()).0;
+
+        let lol_reward = open_box(&mut contract, &bob, Some(&alice));
+
+        let stats = contract.get_referral_stats(alice.clone());
+        assert_eq!((stats.paid_referrals, stats.boxes_opened, stats.premium_earned), (1, 1, 1));
+        assert_eq!(stats.lol_earned.0, lol_reward / 10);
+        assert_eq!(contract.ft_balance_of(alice).0, alice_balance + lol_reward / 10);
+    }
+
+    #[test]
+    fn premium_slots_are_counted_per_unique_paid_referral() {
+        let mut contract = setup_referrals(2);
+        let (alice, bob, carol) = (account("alice.near"), account("bob.near"), account("carol.near"));
+        open_box(&mut contract, &alice, None);
+        let premium_boxes_left = contract.user_premium_boxes_left(alice.clone());
+
+        // Many boxes of one referral don't earn premium slots
+        open_box(&mut contract, &bob, Some(&alice));
+        open_box(&mut contract, &bob, None);
+        open_box(&mut contract, &bob, None);
+        let stats = contract.get_referral_stats(alice.clone());
+        assert_eq!((stats.paid_referrals, stats.boxes_opened, stats.premium_earned), (1, 3, 0));
+
+        open_box(&mut contract, &carol, Some(&alice));
+        let stats = contract.get_referral_stats(alice.clone());
+        assert_eq!((stats.paid_referrals, stats.boxes_opened, stats.premium_earned), (2, 4, 1));
+        assert_eq!(contract.user_premium_boxes_left(alice), premium_boxes_left + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "[REFERRAL_CYCLE]")]
+    fn referral_cycle() {
+        let mut contract = setup_referrals(1);
+        let (alice, bob, carol) = (account("alice.near"), account("bob.near"), account("carol.near"));
+
+        contract._set_referrer(&bob, &alice);
+        contract._set_referrer(&carol, &bob);
+        contract._set_referrer(&alice, &carol);
+    }
+
+    #[test]
+    #[should_panic(expected = "[REFERRAL_CHAIN_TOO_DEEP]")]
+    fn referral_chain_too_deep() {
+        let mut contract = setup_referrals(1);
+        let referral = |index: u32| account(&format!("referral{}.near", index));
+
+        // Referrer of the last account has MAX_REFERRAL_DEPTH + 1 upper referrers
+        for index in 1..=MAX_REFERRAL_DEPTH + 2 {
+            contract._set_referrer(&referral(index), &referral(index - 1));
+        }
+    }
+}
@@ -0,0 +1,512 @@
+use crate::*;
+use near_sdk::assert_one_yocto;
+
+pub const MIN_STAKE_AMOUNT: Balance = 100_000 * ONE_TOKEN;
+pub const MAX_STAKE_POSITIONS: usize = 10;
+pub const UNSTAKE_COOLDOWN: Timestamp = 7 * ONE_DAY;
+// Lock durations in days, benefit level is index + 1
+pub const STAKE_DURATIONS: [u32; 3] = [30, 90, 180];
+// Box price discount per benefit level in basis points
+pub const STAKE_DISCOUNT_BPS: u128 = 500;
+// Extra premium boxes per benefit level
+pub const STAKE_EXTRA_PREMIUM: u32 = 10;
+// LOL reward boost per benefit level in basis points
+pub const STAKE_LOL_BOOST_BPS: u128 = 1000;
+pub const BPS_DENOMINATOR: u128 = 10_000;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum StakeBenefit {
+    Discount,
+    Premium,
+    LolBoost,
+}
+
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakePosition {
+    pub id: u32,
+    pub amount: U128,
+    pub benefit: StakeBenefit,
+    pub level: u32,
+    pub staked_at: Timestamp,
+    pub unlock_at: Timestamp,
+    // Set by unstake, tokens can be withdrawn after cooldown. Position gives no benefits while unstaking
+    pub unstaked_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingBenefits {
+    pub discount_bps: u32,
+    pub extra_premium: u32,
+    pub lol_boost_bps: u32,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_stakes(&self, account_id: AccountId) -> Vec<StakePosition> {
+        self.user_stakes.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn get_staking_benefits(&self, account_id: AccountId) -> StakingBenefits {
+        StakingBenefits {
+            discount_bps: self._stake_discount_bps(&account_id) as u32,
+            extra_premium: self._stake_extra_premium(&account_id),
+            lol_boost_bps: self._stake_lol_boost_bps(&account_id) as u32,
+        }
+    }
+
+    pub fn get_total_staked(&self) -> U128 {
+        self.total_staked.into()
+    }
+
+    // Open box price for account with staking discount
+    pub fn get_box_price(&self, account_id: AccountId) -> U128 {
+        self._box_price(&account_id).into()
+    }
+
+    pub fn get_stake_storage_balance(&self, account_id: AccountId) -> U128 {
+        self._get_user(&account_id).stake_storage_balance.into()
+    }
+
+    // Prepay storage of stake positions, each stake takes its storage cost from this balance
+    #[payable]
+    pub fn deposit_stake_storage(&mut self) -> U128 {
+        let account_id = env::predecessor_account_id();
+        let mut user = self._get_user(&account_id);
+        user.stake_storage_balance += env::attached_deposit();
+        self._save_user(&account_id, &user);
+        self.treasury.stake_storage += env::attached_deposit();
+
+        user.stake_storage_balance.into()
+    }
+
+    // Return unspent stake storage deposit
+    #[payable]
+    pub fn withdraw_stake_storage(&mut self) -> U128 {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let amount = self._refund_stake_storage(&account_id);
+        if amount == 0 {
+            ContractError::WrongAmount.panic();
+        }
+
+        amount.into()
+    }
+
+    // Start unstake cooldown for unlocked position
+    #[payable]
+    pub fn unstake(&mut self, position_id: u32) -> Timestamp {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let now = env::block_timestamp();
+        let mut stakes = self.get_stakes(account_id.clone());
+        let position = stakes.iter_mut()
+            .find(|position| position.id == position_id)
+            .unwrap_or_else(|| ContractError::StakeNotFound.panic());
+        if position.unstaked_at.is_some() {
+            ContractError::AlreadyUnstaking.panic();
+        }
+        if now < position.unlock_at {
+            ContractError::StakeLocked.panic();
+        }
+
+        position.unstaked_at = Some(now);
+        self.user_stakes.insert(&account_id, &stakes);
+        // Unstaking position stops earning staking rewards
+        self._update_staker_shares(&account_id, staker_shares(&stakes));
+
+        log!("Unstake: {}, {}", account_id, position_id);
+        now + UNSTAKE_COOLDOWN
+    }
+
+    // Return unstaked LOL after cooldown
+    #[payable]
+    pub fn withdraw_stake(&mut self, position_id: u32) -> U128 {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let mut stakes = self.get_stakes(account_id.clone());
+        let index = stakes.iter()
+            .position(|position| position.id == position_id)
+            .unwrap_or_else(|| ContractError::StakeNotFound.panic());
+        match stakes[index].unstaked_at {
+            Some(unstaked_at) if env::block_timestamp() >= unstaked_at + UNSTAKE_COOLDOWN => {}
+            _ => ContractError::StakeLocked.panic(),
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        let amount = stakes.remove(index).amount.0;
+        if stakes.is_empty() {
+            self.user_stakes.remove(&account_id);
+        } else {
+            self.user_stakes.insert(&account_id, &stakes);
+        }
+        self.total_staked -= amount;
+        self._release_stake_storage(&account_id, initial_storage_usage);
+        // Nothing is staked anymore, so whole stake storage deposit is returned
+        if stakes.is_empty() {
+            self._refund_stake_storage(&account_id);
+        }
+
+        // Account could close FT storage while tokens were staked
+        if !self.token.accounts.contains_key(&account_id) {
+            self.token.internal_register_account(&account_id);
+        }
+        self.token.internal_transfer(&env::current_account_id(), &account_id, amount, Some("Stake withdrawn".to_string()));
+
+        log!("Stake withdrawn: {}, {}, {}", account_id, position_id, amount);
+        amount.into()
+    }
+}
+
+impl Contract {
+    // Stake LOL received with ft_transfer_call msg {"stake": {"duration_days": 90, "benefit": "discount"}}
+    pub(crate) fn _stake(&mut self, account_id: AccountId, amount: Balance, duration_days: u32, benefit: StakeBenefit) -> PromiseOrValue<U128> {
+        if amount < MIN_STAKE_AMOUNT {
+            ContractError::WrongAmount.panic();
+        }
+        let level = STAKE_DURATIONS.iter()
+            .position(|days| *days == duration_days)
+            .unwrap_or_else(|| ContractError::WrongStakeDuration.panic()) as u32 + 1;
+
+        let mut stakes = self.get_stakes(account_id.clone());
+        if stakes.len() >= MAX_STAKE_POSITIONS {
+            ContractError::TooManyStakes.panic();
+        }
+        let now = env::block_timestamp();
+        let id = stakes.iter().map(|position| position.id + 1).max().unwrap_or(0);
+
+        let initial_storage_usage = env::storage_usage();
+        stakes.push(StakePosition {
+            id,
+            amount: amount.into(),
+            benefit,
+            level,
+            staked_at: now,
+            unlock_at: now + duration_days as u64 * ONE_DAY,
+            unstaked_at: None,
+        });
+        self.user_stakes.insert(&account_id, &stakes);
+        self.total_staked += amount;
+        self._update_staker_shares(&account_id, staker_shares(&stakes));
+
+        // Panic reverts stake, tokens are returned to sender by ft_resolve_transfer
+        let storage_cost = self._record_storage_usage(&account_id, initial_storage_usage);
+        let mut user = self._get_user(&account_id);
+        if user.stake_storage_balance < storage_cost {
+            ContractError::StakeStorageRequired.panic();
+        }
+        user.stake_storage_balance -= storage_cost;
+        self._save_user(&account_id, &user);
+        self.treasury.stake_storage -= storage_cost;
+        self.treasury.storage_fees += storage_cost;
+
+        log!("Stake: {}, {}, {}, {}", account_id, id, amount, duration_days);
+        PromiseOrValue::Value(U128(0))
+    }
+
+    // Storage freed since initial_storage_usage goes back to stake storage balance of account
+    fn _release_stake_storage(&mut self, account_id: &AccountId, initial_storage_usage: u64) {
+        let released = initial_storage_usage.saturating_sub(env::storage_usage());
+        let released_cost = (released as u128 * env::storage_byte_cost()).min(self.treasury.storage_fees);
+
+        let mut user = self._get_user(account_id);
+        user.storage_used = user.storage_used.saturating_sub(released);
+        user.stake_storage_balance += released_cost;
+        self._save_user(account_id, &user);
+        self.treasury.storage_fees -= released_cost;
+        self.treasury.stake_storage += released_cost;
+    }
+
+    fn _refund_stake_storage(&mut self, account_id: &AccountId) -> Balance {
+        let mut user = self._get_user(account_id);
+        let amount = user.stake_storage_balance;
+        if amount == 0 {
+            return 0;
+        }
+
+        user.stake_storage_balance = 0;
+        self._save_user(account_id, &user);
+        self.treasury.stake_storage -= amount;
+        log!("Stake storage refunded: {}, {}", account_id, amount);
+        Promise::new(account_id.clone()).transfer(amount);
+
+        amount
+    }
+
+    fn _active_stakes(&self, account_id: &AccountId, benefit: StakeBenefit) -> Vec<StakePosition> {
+        self.user_stakes.get(account_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|position| position.benefit == benefit && position.unstaked_at.is_none())
+            .collect()
+    }
+
+    // Discount and boost are taken from best position, premium boxes are summed
+    fn _stake_discount_bps(&self, account_id: &AccountId) -> u128 {
+        self._active_stakes(account_id, StakeBenefit::Discount)
+            .iter()
+            .map(|position| position.level as u128 * STAKE_DISCOUNT_BPS)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn _stake_extra_premium(&self, account_id: &AccountId) -> u32 {
+        self._active_stakes(account_id, StakeBenefit::Premium)
+            .iter()
+            .fold(0, |total, position| total.saturating_add(position.level * STAKE_EXTRA_PREMIUM))
+    }
+
+    fn _stake_lol_boost_bps(&self, account_id: &AccountId) -> u128 {
+        self._active_stakes(account_id, StakeBenefit::LolBoost)
+            .iter()
+            .map(|position| position.level as u128 * STAKE_LOL_BOOST_BPS)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn _box_price(&self, account_id: &AccountId) -> Balance {
+        let price = self._current_phase_settings().price.0;
+        price - price * self._stake_discount_bps(account_id) / BPS_DENOMINATOR
+    }
+
+    pub(crate) fn _boosted_lol_amount(&self, account_id: &AccountId, lol_amount: Balance) -> Balance {
+        lol_amount * (BPS_DENOMINATOR + self._stake_lol_boost_bps(account_id)) / BPS_DENOMINATOR
+    }
+}
+
+// LOL staked in positions that are not unstaking
+fn staker_shares(stakes: &[StakePosition]) -> Balance {
+    stakes.iter()
+        .filter(|position| position.unstaked_at.is_none())
+        .map(|position| position.amount.0)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+    use near_contract_standards::storage_management::StorageManagement;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::get_created_receipts;
+    use near_sdk::testing_env;
+
+    const STAKE_STORAGE_DEPOSIT: Balance = ONE_TOKEN / 100;
+
+    // Registered account with LOL balance and prepaid stake storage
+    fn setup_staker(account_id: &AccountId, balance: Balance) -> Contract {
+        let mut contract = setup_contract();
+        contract.token.internal_register_account(account_id);
+        contract.token.internal_transfer(&contract_account(), account_id, balance, None);
+
+        testing_env!(context(account_id).attached_deposit(STAKE_STORAGE_DEPOSIT).build());
+        contract.deposit_stake_storage();
+        contract
+    }
+
+    // ft_transfer_call moves tokens to contract before ft_on_transfer
+    fn stake(contract: &mut Contract, account_id: &AccountId, amount: Balance, duration_days: u32, benefit: &str) {
+        testing_env!(context(&contract_account()).build());
+        contract.token.internal_transfer(account_id, &contract_account(), amount, None);
+        let msg = format!(r#"{{"stake":{{"duration_days":{},"benefit":"{}"}}}}"#, duration_days, benefit);
+        contract.ft_on_transfer(account_id.clone(), amount.into(), msg);
+    }
+
+    fn call_at(account_id: &AccountId, block_timestamp: Timestamp) {
+        testing_env!(context(account_id).block_timestamp(block_timestamp).attached_deposit(1).build());
+    }
+
+    fn transferred_to(account_id: &AccountId) -> Balance {
+        get_created_receipts()
+            .iter()
+            .filter(|receipt| &receipt.receiver_id == account_id)
+            .flat_map(|receipt| receipt.actions.iter())
+            .map(|action| match action {
+                VmAction::Transfer { deposit } => *deposit,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn discount_benefit_reduces_box_price() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, 2 * MIN_STAKE_AMOUNT);
+
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "discount");
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 180, "discount");
+
+        // Best position gives the discount, positions are not summed
+        assert_eq!(contract.get_staking_benefits(alice.clone()).discount_bps, 1500);
+        assert_eq!(contract.get_box_price(alice.clone()).0, OPEN_BOX_PRICE * 85 / 100);
+        assert_eq!(contract.get_total_staked().0, 2 * MIN_STAKE_AMOUNT);
+        assert!(contract.get_stake_storage_balance(alice).0 < STAKE_STORAGE_DEPOSIT);
+    }
+
+    #[test]
+    fn premium_benefit_adds_premium_boxes() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, 2 * MIN_STAKE_AMOUNT);
+        let premium_boxes_left = contract.user_premium_boxes_left(alice.clone());
+
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "premium");
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 90, "premium");
+
+        assert_eq!(contract.get_staking_benefits(alice.clone()).extra_premium, 30);
+        assert_eq!(contract.user_premium_boxes_left(alice), premium_boxes_left + 30);
+    }
+
+    #[test]
+    fn lol_boost_benefit_increases_lol_reward() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, MIN_STAKE_AMOUNT);
+
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 90, "lol_boost");
+
+        assert_eq!(contract.get_staking_benefits(alice.clone()).lol_boost_bps, 2000);
+        assert_eq!(contract._boosted_lol_amount(&alice, 1000 * ONE_TOKEN), 1200 * ONE_TOKEN);
+    }
+
+    #[test]
+    #[should_panic(expected = "[STAKE_STORAGE_REQUIRED]")]
+    fn stake_without_storage_deposit() {
+        let alice = account("alice.near");
+        let mut contract = setup_contract();
+        contract.token.internal_register_account(&alice);
+        contract.token.internal_transfer(&contract_account(), &alice, MIN_STAKE_AMOUNT, None);
+
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "discount");
+    }
+
+    #[test]
+    #[should_panic(expected = "[STAKE_LOCKED]")]
+    fn unstake_before_unlock() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, MIN_STAKE_AMOUNT);
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "discount");
+
+        call_at(&alice, MINT_START_TIMESTAMP + 30 * ONE_DAY - 1);
+        contract.unstake(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "[STAKE_LOCKED]")]
+    fn withdraw_before_cooldown() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, MIN_STAKE_AMOUNT);
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "discount");
+        let unlock_at = MINT_START_TIMESTAMP + 30 * ONE_DAY;
+
+        call_at(&alice, unlock_at);
+        contract.unstake(0);
+
+        call_at(&alice, unlock_at + UNSTAKE_COOLDOWN - 1);
+        contract.withdraw_stake(0);
+    }
+
+    #[test]
+    fn unstake_and_withdraw_after_cooldown() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, MIN_STAKE_AMOUNT);
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "discount");
+        let unlock_at = MINT_START_TIMESTAMP + 30 * ONE_DAY;
+
+        call_at(&alice, unlock_at);
+        assert_eq!(contract.unstake(0), unlock_at + UNSTAKE_COOLDOWN);
+        // Unstaking position gives no benefits
+        assert_eq!(contract.get_staking_benefits(alice.clone()).discount_bps, 0);
+
+        call_at(&alice, unlock_at + UNSTAKE_COOLDOWN);
+        assert_eq!(contract.withdraw_stake(0).0, MIN_STAKE_AMOUNT);
+        assert_eq!(contract.ft_balance_of(alice.clone()).0, MIN_STAKE_AMOUNT);
+        assert!(contract.get_stakes(alice).is_empty());
+        assert_eq!(contract.get_total_staked().0, 0);
+    }
+
+    #[test]
+    fn withdraw_after_ft_account_is_closed() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, MIN_STAKE_AMOUNT);
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "premium");
+        let unlock_at = MINT_START_TIMESTAMP + 30 * ONE_DAY;
+
+        call_at(&alice, unlock_at);
+        assert!(contract.storage_unregister(None));
+        contract.unstake(0);
+
+        call_at(&alice, unlock_at + UNSTAKE_COOLDOWN);
+        contract.withdraw_stake(0);
+        assert_eq!(contract.ft_balance_of(alice).0, MIN_STAKE_AMOUNT);
+    }
+
+    #[test]
+    fn stake_storage_is_not_revenue() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, MIN_STAKE_AMOUNT);
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "discount");
+
+        let treasury = contract.get_treasury();
+        let stake_storage_balance = contract.get_stake_storage_balance(alice).0;
+        assert_eq!(treasury.stake_storage.0, stake_storage_balance);
+        assert_eq!(treasury.storage_fees.0, STAKE_STORAGE_DEPOSIT - stake_storage_balance);
+        assert_eq!(
+            treasury.available_revenue.0,
+            env::account_balance() - treasury.reserve_required.0 - treasury.storage_cost.0 - treasury.stake_storage.0
+        );
+    }
+
+    #[test]
+    fn withdraw_unspent_stake_storage() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, MIN_STAKE_AMOUNT);
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "discount");
+        let stake_storage_balance = contract.get_stake_storage_balance(alice.clone()).0;
+
+        call_at(&alice, MINT_START_TIMESTAMP);
+        assert_eq!(contract.withdraw_stake_storage().0, stake_storage_balance);
+
+        assert_eq!(transferred_to(&alice), stake_storage_balance);
+        assert_eq!(contract.get_stake_storage_balance(alice).0, 0);
+        assert_eq!(contract.get_treasury().stake_storage.0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "[WRONG_AMOUNT]")]
+    fn withdraw_empty_stake_storage() {
+        let mut contract = setup_contract();
+
+        call_at(&account("alice.near"), MINT_START_TIMESTAMP);
+        contract.withdraw_stake_storage();
+    }
+
+    #[test]
+    fn withdraw_stake_releases_storage_and_refunds_after_last_stake() {
+        let alice = account("alice.near");
+        let mut contract = setup_staker(&alice, 2 * MIN_STAKE_AMOUNT);
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "discount");
+        stake(&mut contract, &alice, MIN_STAKE_AMOUNT, 30, "premium");
+        let unlock_at = MINT_START_TIMESTAMP + 30 * ONE_DAY;
+        call_at(&alice, unlock_at);
+        contract.unstake(0);
+        contract.unstake(1);
+
+        let stake_storage_balance = contract.get_stake_storage_balance(alice.clone()).0;
+        let storage_used = contract.get_user_storage(alice.clone()).0;
+        call_at(&alice, unlock_at + UNSTAKE_COOLDOWN);
+        contract.withdraw_stake(0);
+        assert!(contract.get_stake_storage_balance(alice.clone()).0 > stake_storage_balance);
+        assert!(contract.get_user_storage(alice.clone()).0 < storage_used);
+        assert_eq!(transferred_to(&alice), 0);
+
+        let stake_storage_balance = contract.get_stake_storage_balance(alice.clone()).0;
+        call_at(&alice, unlock_at + UNSTAKE_COOLDOWN);
+        contract.withdraw_stake(1);
+        assert!(transferred_to(&alice) > stake_storage_balance);
+        assert_eq!(contract.get_stake_storage_balance(alice).0, 0);
+        assert_eq!(contract.get_treasury().stake_storage.0, 0);
+    }
+}
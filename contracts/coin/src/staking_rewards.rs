@@ -0,0 +1,207 @@
+use crate::*;
+use crate::staking::BPS_DENOMINATOR;
+use crate::utils::mul_div;
+use near_sdk::{is_promise_success, Gas};
+
+// Precision of reward per staked LOL yoctotoken
+pub const REWARD_PER_SHARE_PRECISION: u128 = 1_000_000_000_000_000_000_000_000;
+pub const GAS_FOR_ON_STAKING_REWARDS_CLAIMED: Gas = Gas(10_000_000_000_000);
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct StakingRewardPool {
+    // Part of box payments shared with stakers in basis points
+    pub revenue_share_bps: u32,
+    // Accumulated NEAR reward per staked LOL, scaled by REWARD_PER_SHARE_PRECISION
+    pub acc_reward_per_share: u128,
+    // LOL staked in positions that are not unstaking
+    pub total_shares: Balance,
+    pub total_distributed: Balance,
+    pub total_claimed: Balance,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct StakerRewards {
+    pub shares: Balance,
+    // Rewards for current shares accrued before staker joined, shares * acc_reward_per_share
+    pub reward_debt: Balance,
+    // Accrued and not claimed rewards
+    pub pending: Balance,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingPoolInfo {
+    pub revenue_share_bps: u32,
+    pub acc_reward_per_share: U128,
+    pub total_shares: U128,
+    pub total_distributed: U128,
+    pub total_claimed: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_staking_pool(&self) -> StakingPoolInfo {
+        StakingPoolInfo {
+            revenue_share_bps: self.staking_pool.revenue_share_bps,
+            acc_reward_per_share: self.staking_pool.acc_reward_per_share.into(),
+            total_shares: self.staking_pool.total_shares.into(),
+            total_distributed: self.staking_pool.total_distributed.into(),
+            total_claimed: self.staking_pool.total_claimed.into(),
+        }
+    }
+
+    // NEAR rewards accrued by account and not claimed yet
+    pub fn get_staking_rewards(&self, account_id: AccountId) -> U128 {
+        let rewards = self.user_staking_rewards.get(&account_id).unwrap_or_default();
+        (rewards.pending + self._accrued_staking_rewards(&rewards)).into()
+    }
+
+    pub fn claim_staking_rewards(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let mut rewards = self.user_staking_rewards.get(&account_id).unwrap_or_default();
+        let shares = rewards.shares;
+        self._settle_staking_rewards(&mut rewards, shares);
+
+        let amount = rewards.pending;
+        if amount == 0 {
+            ContractError::NothingToClaim.panic();
+        }
+        rewards.pending = 0;
+        self._save_staker_rewards(&account_id, &rewards);
+
+        self.staking_pool.total_claimed += amount;
+        self.treasury.staking_rewards -= amount;
+        log!("Staking rewards claimed: {}, {}", account_id, amount);
+
+        Promise::new(account_id.clone()).transfer(amount).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_ON_STAKING_REWARDS_CLAIMED)
+                .on_staking_rewards_claimed(account_id, amount.into()),
+        )
+    }
+
+    // Failed transfer returns rewards to pending, returns claimed amount
+    #[private]
+    pub fn on_staking_rewards_claimed(&mut self, account_id: AccountId, amount: U128) -> U128 {
+        if is_promise_success() {
+            return amount;
+        }
+
+        let mut rewards = self.user_staking_rewards.get(&account_id).unwrap_or_default();
+        rewards.pending += amount.0;
+        self._save_staker_rewards(&account_id, &rewards);
+
+        self.staking_pool.total_claimed -= amount.0;
+        self.treasury.staking_rewards += amount.0;
+        log!("Staking rewards claim failed: {}, {}", account_id, amount.0);
+        U128(0)
+    }
+
+    // -------------- Admin functions --------------
+
+    pub fn set_staking_revenue_share(&mut self, revenue_share_bps: u32) {
+        self._assert_owner();
+        if revenue_share_bps as u128 + self.jackpot.contribution_bps as u128 > BPS_DENOMINATOR {
+            ContractError::InvalidRevenueShare.panic();
+        }
+
+        self.staking_pool.revenue_share_bps = revenue_share_bps;
+        log!("Staking revenue share: {}", revenue_share_bps);
+    }
+}
+
+impl Contract {
+    // Share part of box payment with stakers, nothing is shared while no LOL is staked
+    pub(crate) fn _distribute_staking_rewards(&mut self, payment: Balance) {
+        if self.staking_pool.revenue_share_bps == 0 || self.staking_pool.total_shares == 0 {
+            return;
+        }
+
+        let reward = payment * self.staking_pool.revenue_share_bps as u128 / BPS_DENOMINATOR;
+        self.staking_pool.acc_reward_per_share += mul_div(reward, REWARD_PER_SHARE_PRECISION, self.staking_pool.total_shares);
+        self.staking_pool.total_distributed += reward;
+        self.treasury.staking_rewards += reward;
+    }
+
+    // Move accrued rewards to pending and set new shares of account
+    pub(crate) fn _update_staker_shares(&mut self, account_id: &AccountId, shares: Balance) {
+        let mut rewards = self.user_staking_rewards.get(account_id).unwrap_or_default();
+        self.staking_pool.total_shares = self.staking_pool.total_shares - rewards.shares + shares;
+        self._settle_staking_rewards(&mut rewards, shares);
+        self._save_staker_rewards(account_id, &rewards);
+    }
+
+    fn _settle_staking_rewards(&self, rewards: &mut StakerRewards, shares: Balance) {
+        rewards.pending += self._accrued_staking_rewards(rewards);
+        rewards.shares = shares;
+        rewards.reward_debt = mul_div(shares, self.staking_pool.acc_reward_per_share, REWARD_PER_SHARE_PRECISION);
+    }
+
+    fn _accrued_staking_rewards(&self, rewards: &StakerRewards) -> Balance {
+        mul_div(rewards.shares, self.staking_pool.acc_reward_per_share, REWARD_PER_SHARE_PRECISION) - rewards.reward_debt
+    }
+
+    fn _save_staker_rewards(&mut self, account_id: &AccountId, rewards: &StakerRewards) {
+        if rewards.shares == 0 && rewards.pending == 0 {
+            self.user_staking_rewards.remove(account_id);
+        } else {
+            self.user_staking_rewards.insert(account_id, rewards);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::staking::MIN_STAKE_AMOUNT;
+    use near_sdk::{testing_env, PromiseResult};
+
+    // Staker with all shares and pending rewards from one box payment
+    fn setup_rewards(account_id: &AccountId) -> Contract {
+        let mut contract = setup_contract();
+        contract.set_staking_revenue_share(1000);
+        contract._update_staker_shares(account_id, MIN_STAKE_AMOUNT);
+        contract._distribute_staking_rewards(10 * ONE_TOKEN);
+        contract
+    }
+
+    fn claim(contract: &mut Contract, account_id: &AccountId) {
+        testing_env!(context(account_id).build());
+        contract.claim_staking_rewards();
+        assert_eq!(contract.get_staking_rewards(account_id.clone()).0, 0);
+        assert_eq!(contract.get_staking_pool().total_claimed.0, ONE_TOKEN);
+        assert_eq!(contract.get_treasury().staking_rewards.0, 0);
+    }
+
+    #[test]
+    fn claim_transfers_rewards() {
+        let alice = account("alice.near");
+        let mut contract = setup_rewards(&alice);
+        assert_eq!(contract.get_staking_rewards(alice.clone()).0, ONE_TOKEN);
+
+        claim(&mut contract, &alice);
+
+        callback_env(PromiseResult::Successful(vec![]));
+        assert_eq!(contract.on_staking_rewards_claimed(alice.clone(), ONE_TOKEN.into()).0, ONE_TOKEN);
+        assert_eq!(contract.get_staking_rewards(alice).0, 0);
+        assert_eq!(contract.get_staking_pool().total_claimed.0, ONE_TOKEN);
+    }
+
+    #[test]
+    fn failed_claim_restores_rewards() {
+        let alice = account("alice.near");
+        let mut contract = setup_rewards(&alice);
+
+        claim(&mut contract, &alice);
+
+        callback_env(PromiseResult::Failed);
+        assert_eq!(contract.on_staking_rewards_claimed(alice.clone(), ONE_TOKEN.into()).0, 0);
+        assert_eq!(contract.get_staking_rewards(alice.clone()).0, ONE_TOKEN);
+        assert_eq!(contract.get_staking_pool().total_claimed.0, 0);
+        assert_eq!(contract.get_treasury().staking_rewards.0, ONE_TOKEN);
+
+        // Restored rewards can be claimed again
+        claim(&mut contract, &alice);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::*;
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::{testing_env, PromiseResult, RuntimeFeesConfig, VMConfig};
+
+pub fn account(name: &str) -> AccountId {
+    name.parse().unwrap()
+}
+
+pub fn contract_account() -> AccountId {
+    account("lol.near")
+}
+
+pub fn owner() -> AccountId {
+    account("owner.near")
+}
+
+// Call from predecessor_id to contract when public phase is started
+pub fn context(predecessor_id: &AccountId) -> VMContextBuilder {
+    let mut builder = VMContextBuilder::new();
+    builder
+        .current_account_id(contract_account())
+        .signer_account_id(predecessor_id.clone())
+        .predecessor_account_id(predecessor_id.clone())
+        .block_timestamp(MINT_START_TIMESTAMP)
+        .account_balance(100_000 * ONE_TOKEN);
+    builder
+}
+
+// Contract with default config, initialized a day before mint start
+pub fn setup_contract() -> Contract {
+    testing_env!(context(&owner()).block_timestamp(MINT_START_TIMESTAMP - ONE_DAY).build());
+    Contract::new_default_meta(owner())
+}
+
+// Box paid with public price, returns LOL reward
+pub fn open_box(contract: &mut Contract, account_id: &AccountId, referrer: Option<&AccountId>) -> Balance {
+    testing_env!(context(account_id).attached_deposit(OPEN_BOX_PRICE).build());
+    let (_, lol_reward, _) = contract.open_box(referrer.cloned());
+    lol_reward.0
+}
+
+// Callback called by contract itself with result of its promise
+pub fn callback_env(promise_result: PromiseResult) {
+    testing_env!(
+        context(&contract_account()).build(),
+        VMConfig::test(),
+        RuntimeFeesConfig::test(),
+        Default::default(),
+        vec![promise_result],
+    );
+}
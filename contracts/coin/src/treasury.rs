@@ -0,0 +1,178 @@
+use crate::*;
+use near_sdk::{is_promise_success, Gas};
+
+pub const GAS_FOR_ON_REVENUE_WITHDRAWN: Gas = Gas(10_000_000_000_000);
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct Treasury {
+    pub total_collected: Balance,
+    pub total_paid_out: Balance,
+    pub total_withdrawn: Balance,
+    // Part of box deposits that covers storage added by box opening
+    pub storage_fees: Balance,
+    // Paid for reserved boxes, becomes revenue when box is opened or is refunded
+    pub box_escrow: Balance,
+    // Shared with stakers and not claimed yet
+    pub staking_rewards: Balance,
+    // Deposited by users for stake storage and not spent yet
+    pub stake_storage: Balance,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TreasuryInfo {
+    pub total_collected: U128,
+    pub total_paid_out: U128,
+    pub total_withdrawn: U128,
+    pub storage_fees: U128,
+    pub box_escrow: U128,
+    pub staking_rewards: U128,
+    pub stake_storage: U128,
+    pub jackpot: U128,
+    pub reserve_required: U128,
+    pub storage_cost: U128,
+    pub available_revenue: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_treasury(&self) -> TreasuryInfo {
+        TreasuryInfo {
+            total_collected: self.treasury.total_collected.into(),
+            total_paid_out: self.treasury.total_paid_out.into(),
+            total_withdrawn: self.treasury.total_withdrawn.into(),
+            storage_fees: self.treasury.storage_fees.into(),
+            box_escrow: self.treasury.box_escrow.into(),
+            staking_rewards: self.treasury.staking_rewards.into(),
+            stake_storage: self.treasury.stake_storage.into(),
+            jackpot: self.jackpot.amount.into(),
+            reserve_required: self._prize_reserve_required().into(),
+            storage_cost: self._storage_cost().into(),
+            available_revenue: self._available_revenue().into(),
+        }
+    }
+
+    // -------------- Admin functions --------------
+
+    // Withdraw box revenue, prize reserve and storage stake always stay on contract
+    pub fn withdraw_revenue(&mut self, amount: U128, to: AccountId) -> Promise {
+        self._assert_owner();
+        if amount.0 == 0 || amount.0 > self._available_revenue() {
+            ContractError::WrongAmount.panic();
+        }
+
+        self.treasury.total_withdrawn += amount.0;
+        log!("Revenue withdrawn: {}, {}", to, amount.0);
+
+        Promise::new(to.clone()).transfer(amount.into()).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_ON_REVENUE_WITHDRAWN)
+                .on_revenue_withdrawn(to, amount),
+        )
+    }
+
+    // Failed transfer is not counted as withdrawn, returns withdrawn amount
+    #[private]
+    pub fn on_revenue_withdrawn(&mut self, to: AccountId, amount: U128) -> U128 {
+        if is_promise_success() {
+            return amount;
+        }
+
+        self.treasury.total_withdrawn -= amount.0;
+        log!("Revenue withdrawal failed: {}, {}", to, amount.0);
+        U128(0)
+    }
+}
+
+impl Contract {
+    // NEAR needed to pay all prizes left in boxes, boxes can't be opened after campaign finalization
+    pub(crate) fn _prize_reserve_required(&self) -> Balance {
+        if self.campaign_finalized {
+            return 0;
+        }
+
+        self.rewards_remain
+            .iter()
+            .enumerate()
+            .map(|(reward_type_index, remain)| self._get_near_reward_amount(reward_type_index) * *remain as u128)
+            .sum()
+    }
+
+    pub(crate) fn _storage_cost(&self) -> Balance {
+        env::storage_usage() as u128 * env::storage_byte_cost()
+    }
+
+    pub(crate) fn _available_revenue(&self) -> Balance {
+        env::account_balance().saturating_sub(
+            self._prize_reserve_required() + self._storage_cost() + self.treasury.box_escrow + self.treasury.staking_rewards
+                + self.treasury.stake_storage + self.jackpot.amount
+        )
+    }
+
+    // Box payment becomes revenue, part of it is shared with stakers. Jackpot part is taken when box is opened
+    pub(crate) fn _collect_box_payment(&mut self, amount: Balance) {
+        self.treasury.total_collected += amount;
+        self._distribute_staking_rewards(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, PromiseResult};
+
+    fn withdraw(contract: &mut Contract, amount: Balance) {
+        testing_env!(context(&owner()).build());
+        let available_revenue = contract.get_treasury().available_revenue.0;
+        assert!(available_revenue >= amount);
+
+        contract.withdraw_revenue(amount.into(), owner());
+        assert_eq!(contract.get_treasury().total_withdrawn.0, amount);
+    }
+
+    #[test]
+    fn withdraw_revenue_is_counted() {
+        let mut contract = setup_contract();
+        withdraw(&mut contract, ONE_TOKEN);
+
+        callback_env(PromiseResult::Successful(vec![]));
+        assert_eq!(contract.on_revenue_withdrawn(owner(), ONE_TOKEN.into()).0, ONE_TOKEN);
+        assert_eq!(contract.get_treasury().total_withdrawn.0, ONE_TOKEN);
+    }
+
+    #[test]
+    fn failed_withdrawal_is_reverted() {
+        let mut contract = setup_contract();
+        withdraw(&mut contract, ONE_TOKEN);
+
+        callback_env(PromiseResult::Failed);
+        assert_eq!(contract.on_revenue_withdrawn(owner(), ONE_TOKEN.into()).0, 0);
+        assert_eq!(contract.get_treasury().total_withdrawn.0, 0);
+    }
+
+    #[test]
+    fn prize_reserve_is_released_by_finalization() {
+        let mut contract = setup_contract();
+        assert!(contract.get_treasury().reserve_required.0 > 0);
+        contract.config.campaign_end_timestamp = Some(MINT_START_TIMESTAMP + ONE_DAY);
+
+        testing_env!(context(&owner()).block_timestamp(MINT_START_TIMESTAMP + ONE_DAY).build());
+        contract.finalize_campaign();
+
+        let treasury = contract.get_treasury();
+        assert_eq!(treasury.reserve_required.0, 0);
+        assert_eq!(treasury.available_revenue.0, env::account_balance() - treasury.storage_cost.0);
+        contract.withdraw_revenue(treasury.available_revenue, owner());
+        assert_eq!(contract.get_treasury().total_withdrawn, treasury.available_revenue);
+    }
+
+    #[test]
+    #[should_panic(expected = "[WRONG_AMOUNT]")]
+    fn withdraw_over_available_revenue() {
+        let mut contract = setup_contract();
+        let available_revenue = contract.get_treasury().available_revenue.0;
+
+        contract.withdraw_revenue((available_revenue + 1).into(), owner());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::*;
+use near_sdk::assert_one_yocto;
+
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+pub struct UserState {
+    pub near_reward: u128,
+    pub lol_reward: u128,
+    pub total_box_opened: u32,
+    pub premium_box_opened: u32,
+    // Boxes paid with NEAR or LOL, only accounts with paid boxes earn referral rewards
+    pub paid_boxes_opened: u32,
+    // Contract storage bytes added by user calls
+    pub storage_used: u64,
+    // FT account was registered by free welcome box, so its storage deposit is not refunded by storage_unregister
+    pub welcome_registered: bool,
+    // NEAR deposited for storage of stakes, they are received with ft_transfer_call which can't attach NEAR
+    pub stake_storage_balance: Balance,
+    pub last_daily_box_at: Timestamp,
+    // Days in a row with claimed daily box
+    pub daily_streak: u32,
+    // Daily boxes are counted separately, they don't count against account limit and welcome box eligibility
+    pub daily_boxes_opened: u32,
+    // Bought and not opened boxes with total price paid for them
+    pub inventory_count: u32,
+    pub inventory_paid: u128,
+    // Box NFTs bought by account, they count against account limit even after they are transferred
+    pub box_nfts_bought: u32,
+    pub referrer: Option<AccountId>,
+    // Permanent extra premium boxes, expiring ones are kept in premium_grants
+    pub additional_premium: u32,
+    pub premium_grants: Vec<PremiumGrant>,
+    pub rate_limit: RateLimitState,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_user_storage(&self, account_id: AccountId) -> U64 {
+        self._get_user(&account_id).storage_used.into()
+    }
+}
+
+impl Contract {
+    pub(crate) fn _get_user(&self, account_id: &AccountId) -> UserState {
+        self.users.get(account_id)
+            .or_else(|| self._get_legacy_user(account_id))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn _save_user(&mut self, account_id: &AccountId, user: &UserState) {
+        if self.users.insert(account_id, user).is_none() {
+            self._remove_legacy_user(account_id);
+        }
+    }
+
+    // Users of the first deployment are kept in separate maps until their first update.
+    // First deployment had only boxes paid with NEAR
+    fn _get_legacy_user(&self, account_id: &AccountId) -> Option<UserState> {
+        let mut user = UserState {
+            near_reward: LookupMap::<AccountId, u128>::new(StorageKeys::UserNearReward).get(account_id).unwrap_or(0),
+            lol_reward: LookupMap::<AccountId, u128>::new(StorageKeys::UserLolReward).get(account_id).unwrap_or(0),
+            total_box_opened: LookupMap::<AccountId, u32>::new(StorageKeys::UserTotalBoxOpened).get(account_id).unwrap_or(0),
+            premium_box_opened: LookupMap::<AccountId, u32>::new(StorageKeys::UserPremiumBoxOpened).get(account_id).unwrap_or(0),
+            additional_premium: LookupMap::<AccountId, u32>::new(StorageKeys::UserAdditionalPremium).get(account_id).unwrap_or(0),
+            ..Default::default()
+        };
+        if user.total_box_opened == 0 && user.additional_premium == 0 {
+            return None;
+        }
+        user.paid_boxes_opened = user.total_box_opened;
+        Some(user)
+    }
+
+    fn _remove_legacy_user(&mut self, account_id: &AccountId) {
+        LookupMap::<AccountId, u128>::new(StorageKeys::UserNearReward).remove(account_id);
+        LookupMap::<AccountId, u128>::new(StorageKeys::UserLolReward).remove(account_id);
+        LookupMap::<AccountId, u32>::new(StorageKeys::UserTotalBoxOpened).remove(account_id);
+        LookupMap::<AccountId, u32>::new(StorageKeys::UserPremiumBoxOpened).remove(account_id);
+        LookupMap::<AccountId, u32>::new(StorageKeys::UserAdditionalPremium).remove(account_id);
+    }
+
+    // Clean up closed account data. Box counters, rate limit windows and daily box time are kept as tombstone,
+    // so premium, phase and rate limits can't be reset by closing and registering account again.
+    // Paid inventory and referrer are kept too.
+    // Achievement badges, stakes and staking rewards stay with account, so storage_used and stake storage balance are kept
+    pub(crate) fn _close_user(&mut self, account_id: &AccountId) {
+        if let Some(user) = self.users.get(account_id).or_else(|| self._get_legacy_user(account_id)) {
+            let tombstone = UserState {
+                storage_used: user.storage_used,
+                stake_storage_balance: user.stake_storage_balance,
+                total_box_opened: user.total_box_opened,
+                premium_box_opened: user.premium_box_opened,
+                paid_boxes_opened: user.paid_boxes_opened,
+                last_daily_box_at: user.last_daily_box_at,
+                inventory_count: user.inventory_count,
+                inventory_paid: user.inventory_paid,
+                box_nfts_bought: user.box_nfts_bought,
+                referrer: user.referrer,
+                rate_limit: user.rate_limit,
+                ..Default::default()
+            };
+            self._save_user(account_id, &tombstone);
+        }
+
+        self.user_burned.remove(account_id);
+        self.referral_stats.remove(account_id);
+
+        self.last_participants.retain(|participant| participant != account_id);
+        for leaderboard in [
+            &mut self.near_leaderboard,
+            &mut self.lol_leaderboard,
+            &mut self.burn_leaderboard,
+            &mut self.referral_leaderboard,
+        ] {
+            leaderboard.retain(|item| &item.account_id != account_id);
+        }
+    }
+
+    // FT internal_storage_unregister without refund of storage deposit
+    pub(crate) fn _unregister_without_refund(&mut self, force: Option<bool>) -> Option<(AccountId, Balance)> {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = match self.token.accounts.get(&account_id) {
+            Some(balance) => balance,
+            None => {
+                log!("The account {} is not registered", &account_id);
+                return None;
+            }
+        };
+        if balance > 0 && !force.unwrap_or(false) {
+            env::panic_str("Can't unregister the account with the positive balance without force");
+        }
+
+        self.token.accounts.remove(&account_id);
+        self.token.total_supply -= balance;
+        Some((account_id, balance))
+    }
+
+    // Record storage added since initial_storage_usage to user, returns its cost
+    pub(crate) fn _record_storage_usage(&mut self, account_id: &AccountId, initial_storage_usage: u64) -> Balance {
+        let storage_used = env::storage_usage().saturating_sub(initial_storage_usage);
+        if storage_used == 0 {
+            return 0;
+        }
+
+        let mut user = self._get_user(account_id);
+        user.storage_used += storage_used;
+        self._save_user(account_id, &user);
+
+        storage_used as u128 * env::storage_byte_cost()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+
+    fn force_unregister(contract: &mut Contract, account_id: &AccountId) {
+        testing_env!(context(account_id).attached_deposit(1).build());
+        assert!(contract.storage_unregister(Some(true)));
+    }
+
+    #[test]
+    fn close_account_burns_balance_and_removes_from_lists() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        open_box(&mut contract, &alice, None);
+        let balance = contract.ft_balance_of(alice.clone()).0;
+        let total_supply = contract.ft_total_supply().0;
+        assert!(balance > 0);
+        assert!(contract.get_all_participants().iter().any(|item| item.account_id == alice));
+
+        force_unregister(&mut contract, &alice);
+
+        assert_eq!(contract.ft_total_supply().0, total_supply - balance);
+        assert_eq!(contract.get_burned_total().0, balance);
+        assert!(contract.get_all_participants().iter().all(|item| item.account_id != alice));
+        let (near_leaderboard, lol_leaderboard) = contract.get_leaderboards();
+        assert!(near_leaderboard.iter().chain(lol_leaderboard.iter()).all(|item| item.account_id != alice));
+    }
+
+    #[test]
+    fn close_account_keeps_tombstone() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        open_box(&mut contract, &alice, None);
+        let premium_boxes_left = contract.user_premium_boxes_left(alice.clone());
+        let storage_used = contract.get_user_storage(alice.clone());
+        let achievements = contract.get_achievements(alice.clone()).len();
+        assert!(achievements > 0);
+
+        force_unregister(&mut contract, &alice);
+
+        // Rewards are reset, box counters and data left in storage are kept
+        assert_eq!(contract.get_user_rewards(alice.clone()), (U128(0), U128(0), 1));
+        assert_eq!(contract.user_premium_boxes_left(alice.clone()), premium_boxes_left);
+        assert_eq!(contract.get_user_storage(alice.clone()), storage_used);
+        assert_eq!(contract.get_achievements(alice.clone()).len(), achievements);
+
+        // Registered again account continues with the same counters
+        open_box(&mut contract, &alice, None);
+        assert_eq!(contract.get_user_rewards(alice).2, 2);
+    }
+
+    #[test]
+    fn close_account_without_boxes_keeps_no_user_state() {
+        let mut contract = setup_contract();
+        let alice = account("alice.near");
+        testing_env!(context(&alice).attached_deposit(contract.storage_balance_bounds().min.0).build());
+        contract.storage_deposit(None, None);
+
+        force_unregister(&mut contract, &alice);
+
+        assert!(contract.users.get(&alice).is_none());
+        assert_eq!(contract.get_burned_total().0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "[ACCOUNT_BLOCK_LIMIT_REACHED]")]
+    fn close_account_keeps_block_limit() {
+        let mut contract = setup_contract();
+        contract.set_rate_limit_config(RateLimitConfig { account_per_block: Some(1), ..Default::default() });
+        let alice = account("alice.near");
+        open_box(&mut contract, &alice, None);
+
+        force_unregister(&mut contract, &alice);
+        open_box(&mut contract, &alice, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "[ACCOUNT_DAILY_LIMIT_REACHED]")]
+    fn close_account_keeps_daily_limit() {
+        let mut contract = setup_contract();
+        contract.set_rate_limit_config(RateLimitConfig { account_per_day: Some(1), ..Default::default() });
+        let alice = account("alice.near");
+        open_box(&mut contract, &alice, None);
+
+        force_unregister(&mut contract, &alice);
+        testing_env!(context(&alice).block_index(1).block_timestamp(MINT_START_TIMESTAMP + 1).attached_deposit(OPEN_BOX_PRICE).build());
+        contract.open_box(None);
+    }
+}
@@ -1,19 +1,85 @@
 use crate::*;
 
+#[allow(clippy::all)]
+mod uint_types {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+}
+
+pub(crate) use uint_types::U256;
+
 impl Contract {
+    // Random seed mixed with boxes counter, so every box opened in one call gets own random values
+    fn box_random_seed(&self) -> Vec<u8> {
+        let mut seed = env::random_seed();
+        seed.extend_from_slice(&self.total_box_remain.to_le_bytes());
+        env::sha256(&seed)
+    }
+
     // Generate random u8 number (0-254)
     pub(crate) fn random_u8(&self, index: usize) -> u8 {
-        *env::random_seed().get(index).unwrap()
+        *self.box_random_seed().get(index).unwrap()
+    }
+
+    // Generate random u32 number from 4 seed bytes starting at index
+    pub(crate) fn random_u32(&self, index: usize) -> u32 {
+        let seed = self.box_random_seed();
+        u32::from_le_bytes(seed[index..index + 4].try_into().unwrap())
     }
 
     // Get random number from 0 to max
     pub(crate) fn random_in_range(&self, index: usize, max: usize) -> u32 {
+        u8_in_range(self.random_u8(index), max)
+    }
+}
+
+// Scale random u8 to 0..=max, exact floor of random * (max + 1) / 256. Former f64 form
+// random / (256 / (max + 1)) gives the same value except when random * (max + 1) is a multiple of 256
+// and the divider is not exact, there rounding error gives one less (128 with max 185: 92 instead of 93)
+pub(crate) fn u8_in_range(random: u8, max: usize) -> u32 {
+    (random as u64 * (max as u64 + 1) / 256) as u32
+}
+
+// Calculate amount * numerator / denominator without u128 overflow
+pub(crate) fn mul_div(amount: u128, numerator: u128, denominator: u128) -> u128 {
+    (U256::from(amount) * U256::from(numerator) / U256::from(denominator)).as_u128()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f64_in_range(random: u8, max: usize) -> u32 {
         if max > 0 {
             let rand_divider = 256f64 / (max + 1) as f64;
-            let result = self.random_u8(index) as f64 / rand_divider;
-            return result as u32;
+            return (random as f64 / rand_divider) as u32;
         }
-        0u32
+        0
     }
 
+    #[test]
+    fn u8_in_range_differs_from_f64_form_only_on_exact_boundaries() {
+        // Box counts of reward distribution, LOL ranges of boxes and daily boxes, limits
+        for max in [0, 1, 2, 9, 99, 185, 254, 255, 256, 1000, 4999, 44446, 49999, u32::MAX as usize] {
+            let mut differences = 0;
+            for random in 0..=u8::MAX {
+                let expected = f64_in_range(random, max);
+                let actual = u8_in_range(random, max);
+                assert!(actual as usize <= max);
+                if actual != expected {
+                    assert_eq!(actual, expected + 1, "random {}, max {}", random, max);
+                    assert_eq!(random as u64 * (max as u64 + 1) % 256, 0, "random {}, max {}", random, max);
+                    differences += 1;
+                }
+            }
+            let expected_differences = match max {
+                185 => 1,
+                49999 => 8,
+                _ => 0,
+            };
+            assert_eq!(differences, expected_differences, "max {}", max);
+        }
+        assert_eq!((f64_in_range(128, 185), u8_in_range(128, 185)), (92, 93));
+    }
 }
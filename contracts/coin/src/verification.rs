@@ -0,0 +1,232 @@
+use crate::*;
+use near_sdk::{ext_contract, Gas, PromiseError};
+
+pub const GAS_FOR_VERIFICATION: Gas = Gas(10_000_000_000_000);
+pub const GAS_FOR_ON_VERIFICATION: Gas = Gas(10_000_000_000_000);
+
+// Premium boxes can require verified account to make Sybil attacks harder
+#[derive(Clone, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationMode {
+    Disabled,
+    // Owner-managed registry of verified accounts
+    Registry,
+    // Verifier contract is called by request_verification, result is cached for cache_duration
+    Oracle { verifier_id: AccountId, cache_duration: Timestamp },
+}
+
+#[allow(dead_code)]
+#[ext_contract(ext_verifier)]
+pub trait Verifier {
+    fn is_verified(&self, account_id: AccountId) -> bool;
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_verification_mode(&self) -> &VerificationMode {
+        &self.verification_mode
+    }
+
+    pub fn is_verified(&self, account_id: AccountId) -> bool {
+        self._is_verified(&account_id)
+    }
+
+    // Ask verifier contract about caller account and cache result
+    pub fn request_verification(&mut self) -> Promise {
+        let verifier_id = match &self.verification_mode {
+            VerificationMode::Oracle { verifier_id, .. } => verifier_id.clone(),
+            _ => ContractError::VerifierNotUsed.panic(),
+        };
+
+        let account_id = env::predecessor_account_id();
+        ext_verifier::ext(verifier_id)
+            .with_static_gas(GAS_FOR_VERIFICATION)
+            .is_verified(account_id.clone())
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_ON_VERIFICATION)
+                    .on_verification(account_id),
+            )
+    }
+
+    #[private]
+    pub fn on_verification(&mut self, account_id: AccountId, #[callback_result] result: Result<bool, PromiseError>) -> bool {
+        let cache_duration = match &self.verification_mode {
+            VerificationMode::Oracle { cache_duration, .. } => *cache_duration,
+            _ => return false,
+        };
+
+        let is_verified = result.unwrap_or(false);
+        if is_verified {
+            self.verified_accounts.insert(&account_id, &(env::block_timestamp() + cache_duration));
+        } else {
+            self.verified_accounts.remove(&account_id);
+        }
+
+        log!("Verification: {}, {}", account_id, is_verified);
+        is_verified
+    }
+
+    // -------------- Admin functions --------------
+
+    pub fn set_verification_mode(&mut self, verification_mode: VerificationMode) {
+        self._assert_owner();
+
+        self.verification_mode = verification_mode;
+    }
+
+    // Registry mode: mark accounts as verified or remove verification
+    pub fn set_verified(&mut self, account_ids: Vec<AccountId>, is_verified: bool) {
+        self._assert_owner();
+
+        account_ids.iter().for_each(|account_id| {
+            if is_verified {
+                self.verified_accounts.insert(account_id, &Timestamp::MAX);
+            } else {
+                self.verified_accounts.remove(account_id);
+            }
+        });
+    }
+}
+
+impl Contract {
+    pub(crate) fn _is_verified(&self, account_id: &AccountId) -> bool {
+        match self.verification_mode {
+            VerificationMode::Disabled => true,
+            _ => self.verified_accounts
+                .get(account_id)
+                .map(|verified_until| verified_until > env::block_timestamp())
+                .unwrap_or(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use mock_verifier::MockVerifier;
+    use near_sdk::test_utils::{get_created_receipts, VMContextBuilder};
+    use near_sdk::mock::VmAction;
+    use near_sdk::testing_env;
+
+    const CACHE_DURATION: Timestamp = ONE_DAY;
+
+    fn verifier_account() -> AccountId {
+        account("verifier.near")
+    }
+
+    fn setup_oracle() -> (Contract, MockVerifier) {
+        let mut contract = setup_contract();
+        contract.set_verification_mode(VerificationMode::Oracle {
+            verifier_id: verifier_account(),
+            cache_duration: CACHE_DURATION,
+        });
+
+        testing_env!(verifier_context(&owner()).build());
+        let verifier = MockVerifier::new(owner());
+        (contract, verifier)
+    }
+
+    fn verifier_context(predecessor_id: &AccountId) -> VMContextBuilder {
+        let mut builder = context(predecessor_id);
+        builder.current_account_id(verifier_account());
+        builder
+    }
+
+    // Result of verifier call is passed to the callback the same way the runtime does
+    fn on_verification(contract: &mut Contract, verifier: &MockVerifier, account_id: &AccountId, block_timestamp: Timestamp) -> bool {
+        testing_env!(verifier_context(&contract_account()).block_timestamp(block_timestamp).build());
+        let result = verifier.is_verified(account_id.clone());
+
+        testing_env!(context(&contract_account()).block_timestamp(block_timestamp).build());
+        contract.on_verification(account_id.clone(), Ok(result))
+    }
+
+    #[test]
+    fn request_verification_calls_verifier() {
+        let (mut contract, _) = setup_oracle();
+        let alice = account("alice.near");
+
+        testing_env!(context(&alice).build());
+        contract.request_verification();
+
+        let receipts = get_created_receipts();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].receiver_id, verifier_account());
+        assert!(matches!(
+            &receipts[0].actions[0],
+            VmAction::FunctionCall { function_name, args, .. }
+                if function_name == "is_verified" && args == br#"{"account_id":"alice.near"}"#
+        ));
+        assert_eq!(receipts[1].receiver_id, contract_account());
+        assert!(matches!(
+            &receipts[1].actions[0],
+            VmAction::FunctionCall { function_name, .. } if function_name == "on_verification"
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "[VERIFIER_NOT_USED]")]
+    fn request_verification_without_oracle() {
+        let mut contract = setup_contract();
+
+        testing_env!(context(&account("alice.near")).build());
+        contract.request_verification();
+    }
+
+    #[test]
+    fn verified_account_is_cached_until_expiry() {
+        let (mut contract, mut verifier) = setup_oracle();
+        let alice = account("alice.near");
+
+        testing_env!(verifier_context(&owner()).build());
+        verifier.set_verified(alice.clone(), true);
+
+        assert!(on_verification(&mut contract, &verifier, &alice, MINT_START_TIMESTAMP));
+        assert!(contract.is_verified(alice.clone()));
+
+        testing_env!(context(&alice).block_timestamp(MINT_START_TIMESTAMP + CACHE_DURATION - 1).build());
+        assert!(contract.is_verified(alice.clone()));
+
+        testing_env!(context(&alice).block_timestamp(MINT_START_TIMESTAMP + CACHE_DURATION).build());
+        assert!(!contract.is_verified(alice.clone()));
+
+        // Expired result is refreshed by new request
+        assert!(on_verification(&mut contract, &verifier, &alice, MINT_START_TIMESTAMP + CACHE_DURATION));
+        assert!(contract.is_verified(alice));
+    }
+
+    #[test]
+    fn unverified_account_removes_cached_result() {
+        let (mut contract, mut verifier) = setup_oracle();
+        let alice = account("alice.near");
+
+        assert!(!on_verification(&mut contract, &verifier, &alice, MINT_START_TIMESTAMP));
+        assert!(!contract.is_verified(alice.clone()));
+
+        testing_env!(verifier_context(&owner()).build());
+        verifier.set_verified(alice.clone(), true);
+        assert!(on_verification(&mut contract, &verifier, &alice, MINT_START_TIMESTAMP));
+
+        testing_env!(verifier_context(&owner()).build());
+        verifier.set_verified(alice.clone(), false);
+        assert!(!on_verification(&mut contract, &verifier, &alice, MINT_START_TIMESTAMP + 1));
+        assert!(!contract.is_verified(alice));
+    }
+
+    #[test]
+    fn failed_verifier_call_removes_cached_result() {
+        let (mut contract, mut verifier) = setup_oracle();
+        let alice = account("alice.near");
+
+        testing_env!(verifier_context(&owner()).build());
+        verifier.set_verified(alice.clone(), true);
+        assert!(on_verification(&mut contract, &verifier, &alice, MINT_START_TIMESTAMP));
+
+        testing_env!(context(&contract_account()).build());
+        assert!(!contract.on_verification(alice.clone(), Err(PromiseError::Failed)));
+        assert!(!contract.is_verified(alice));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::*;
+use crate::utils::mul_div;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct VestingSchedule {
+    pub beneficiary_id: AccountId,
+    pub total_amount: Balance,
+    pub released_amount: Balance,
+    pub start_timestamp: Timestamp,
+    pub cliff_timestamp: Timestamp,
+    pub end_timestamp: Timestamp,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingInfo {
+    pub beneficiary_id: AccountId,
+    pub total_amount: U128,
+    pub released_amount: U128,
+    pub locked_amount: U128,
+    pub unlocked_amount: U128,
+    pub start_timestamp: Timestamp,
+    pub cliff_timestamp: Timestamp,
+    pub end_timestamp: Timestamp,
+}
+
+impl VestingSchedule {
+    pub fn new(beneficiary_id: AccountId, total_amount: Balance, start_timestamp: Timestamp, cliff: Timestamp, duration: Timestamp) -> Self {
+        if cliff > duration {
+            ContractError::InvalidVestingCliff.panic();
+        }
+
+        Self {
+            beneficiary_id,
+            total_amount,
+            released_amount: 0,
+            start_timestamp,
+            cliff_timestamp: start_timestamp + cliff,
+            end_timestamp: start_timestamp + duration,
+        }
+    }
+
+    // Total amount unlocked by schedule at timestamp, including already released tokens
+    pub fn vested_amount(&self, timestamp: Timestamp) -> Balance {
+        if timestamp < self.cliff_timestamp {
+            0
+        } else if timestamp >= self.end_timestamp {
+            self.total_amount
+        } else {
+            let elapsed = (timestamp - self.start_timestamp) as u128;
+            let duration = (self.end_timestamp - self.start_timestamp) as u128;
+            mul_div(self.total_amount, elapsed, duration)
+        }
+    }
+
+    // Unlocked amount that is not released yet
+    pub fn releasable_amount(&self, timestamp: Timestamp) -> Balance {
+        self.vested_amount(timestamp) - self.released_amount
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Transfer unlocked LP tokens to beneficiary, anyone can call
+    pub fn release_vested(&mut self) -> U128 {
+        let amount = self.lp_vesting.releasable_amount(env::block_timestamp());
+        if amount == 0 {
+            ContractError::NothingToRelease.panic();
+        }
+
+        self.lp_vesting.released_amount += amount;
+        let beneficiary_id = self.lp_vesting.beneficiary_id.clone();
+        self.token.internal_transfer(&env::current_account_id(), &beneficiary_id, amount, Some("LP vesting release".to_string()));
+        log!("Vesting released: {}, {}", beneficiary_id, amount);
+
+        amount.into()
+    }
+
+    pub fn get_lp_vesting(&self) -> VestingInfo {
+        let vesting = &self.lp_vesting;
+        let vested_amount = vesting.vested_amount(env::block_timestamp());
+
+        VestingInfo {
+            beneficiary_id: vesting.beneficiary_id.clone(),
+            total_amount: vesting.total_amount.into(),
+            released_amount: vesting.released_amount.into(),
+            locked_amount: (vesting.total_amount - vested_amount).into(),
+            unlocked_amount: (vested_amount - vesting.released_amount).into(),
+            start_timestamp: vesting.start_timestamp,
+            cliff_timestamp: vesting.cliff_timestamp,
+            end_timestamp: vesting.end_timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::testing_env;
+
+    fn release_at(contract: &mut Contract, timestamp: Timestamp) -> Balance {
+        testing_env!(context(&account("alice.near")).block_timestamp(timestamp).build());
+        contract.release_vested().0
+    }
+
+    fn view_at(contract: &Contract, timestamp: Timestamp) -> VestingInfo {
+        testing_env!(context(&account("alice.near")).block_timestamp(timestamp).build());
+        contract.get_lp_vesting()
+    }
+
+    #[test]
+    fn schedule_is_linear_after_cliff() {
+        let schedule = VestingSchedule::new(account("lp.near"), 1000, 100, 200, 1000);
+
+        assert_eq!(schedule.vested_amount(299), 0);
+        assert_eq!(schedule.vested_amount(300), 200);
+        assert_eq!(schedule.vested_amount(600), 500);
+        assert_eq!(schedule.vested_amount(1100), 1000);
+        assert_eq!(schedule.vested_amount(u64::MAX), 1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "[INVALID_VESTING_CLIFF]")]
+    fn cliff_after_schedule_end() {
+        VestingSchedule::new(account("lp.near"), 1000, 100, 1001, 1000);
+    }
+
+    #[test]
+    fn nothing_is_unlocked_before_cliff() {
+        let contract = setup_contract();
+        let vesting = contract.get_lp_vesting();
+
+        let info = view_at(&contract, vesting.cliff_timestamp - 1);
+        assert_eq!(info.unlocked_amount.0, 0);
+        assert_eq!(info.locked_amount.0, LP_TOKENS_AMOUNT);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOTHING_TO_RELEASE]")]
+    fn release_before_cliff() {
+        let mut contract = setup_contract();
+        let cliff_timestamp = contract.get_lp_vesting().cliff_timestamp;
+
+        release_at(&mut contract, cliff_timestamp - 1);
+    }
+
+    #[test]
+    fn release_at_midpoint() {
+        let mut contract = setup_contract();
+        let vesting = contract.get_lp_vesting();
+        let midpoint = vesting.start_timestamp + LP_VESTING_DURATION / 2;
+
+        assert_eq!(release_at(&mut contract, midpoint), LP_TOKENS_AMOUNT / 2);
+
+        let info = view_at(&contract, midpoint);
+        assert_eq!(info.released_amount.0, LP_TOKENS_AMOUNT / 2);
+        assert_eq!(info.unlocked_amount.0, 0);
+        assert_eq!(info.locked_amount.0, LP_TOKENS_AMOUNT / 2);
+        assert_eq!(contract.ft_balance_of(vesting.beneficiary_id).0, LP_TOKENS_AMOUNT / 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOTHING_TO_RELEASE]")]
+    fn release_twice_at_the_same_time() {
+        let mut contract = setup_contract();
+        let midpoint = contract.get_lp_vesting().start_timestamp + LP_VESTING_DURATION / 2;
+        release_at(&mut contract, midpoint);
+
+        release_at(&mut contract, midpoint);
+    }
+
+    #[test]
+    fn repeated_releases_sum_to_total() {
+        let mut contract = setup_contract();
+        let vesting = contract.get_lp_vesting();
+
+        let mut released = release_at(&mut contract, vesting.start_timestamp + LP_VESTING_DURATION / 3);
+        released += release_at(&mut contract, vesting.start_timestamp + LP_VESTING_DURATION / 2);
+        released += release_at(&mut contract, vesting.end_timestamp);
+
+        assert_eq!(released, LP_TOKENS_AMOUNT);
+        assert_eq!(contract.ft_balance_of(vesting.beneficiary_id).0, LP_TOKENS_AMOUNT);
+        let info = view_at(&contract, vesting.end_timestamp + ONE_DAY);
+        assert_eq!((info.locked_amount.0, info.unlocked_amount.0, info.released_amount.0), (0, 0, LP_TOKENS_AMOUNT));
+    }
+}
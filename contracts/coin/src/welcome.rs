@@ -0,0 +1,177 @@
+use crate::*;
+
+// Free welcome box for new users, opened through NEP-366 delegate action submitted by approved relayer.
+// Relayer pays gas, predecessor is the user, so box counters and rewards go to the user.
+#[near_bindgen]
+impl Contract {
+    pub fn claim_welcome_box(&mut self) -> (usize, U128, U128) {
+        let owner_id = env::predecessor_account_id();
+        let relayer_id = env::signer_account_id();
+        if relayer_id == owner_id || !self.welcome_relayers.contains(&relayer_id) {
+            ContractError::NotRelayed.panic();
+        }
+        if self.welcome_boxes_remain == 0 {
+            ContractError::NoWelcomeBoxes.panic();
+        }
+        if self._get_user(&owner_id).total_box_opened > 0 {
+            ContractError::WelcomeBoxClaimed.panic();
+        }
+        self._assert_can_open_boxes(&owner_id);
+
+        let initial_storage_usage = env::storage_usage();
+        let is_registered = self.token.accounts.contains_key(&owner_id);
+        self.welcome_boxes_remain -= 1;
+        self.welcome_boxes_opened += 1;
+        // Free box doesn't pay for storage, so achievement badges are not minted, and referrer is not rewarded
+        let result = self._open_box(owner_id.clone(), BoxPayment::Free);
+        // FT account registered by free box has no storage deposit to refund
+        if !is_registered {
+            let mut user = self._get_user(&owner_id);
+            user.welcome_registered = true;
+            self._save_user(&owner_id, &user);
+        }
+        self._record_storage_usage(&owner_id, initial_storage_usage);
+
+        log!("Welcome box: {}, {}", owner_id, relayer_id);
+        result
+    }
+
+    pub fn get_welcome_boxes(&self) -> (u32, u32) {
+        (self.welcome_boxes_remain, self.welcome_boxes_opened)
+    }
+
+    pub fn is_welcome_relayer(&self, account_id: AccountId) -> bool {
+        self.welcome_relayers.contains(&account_id)
+    }
+
+    // -------------- Admin functions --------------
+
+    pub fn set_welcome_boxes(&mut self, count: u32) {
+        self._assert_owner();
+
+        self.welcome_boxes_remain = count;
+    }
+
+    pub fn add_welcome_relayer(&mut self, account_id: AccountId) {
+        self._assert_owner();
+
+        self.welcome_relayers.insert(&account_id);
+    }
+
+    pub fn remove_welcome_relayer(&mut self, account_id: AccountId) {
+        self._assert_owner();
+
+        self.welcome_relayers.remove(&account_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::mock::VmAction;
+    use near_sdk::test_utils::get_created_receipts;
+    use near_sdk::testing_env;
+
+    fn relayer() -> AccountId {
+        account("relayer.near")
+    }
+
+    fn setup_welcome(count: u32) -> Contract {
+        let mut contract = setup_contract();
+        contract.set_welcome_boxes(count);
+        contract.add_welcome_relayer(relayer());
+        contract
+    }
+
+    // Delegate action is executed with relayer as signer and user as predecessor
+    fn claim_relayed(contract: &mut Contract, owner_id: &AccountId, relayer_id: &AccountId) {
+        testing_env!(context(owner_id).signer_account_id(relayer_id.clone()).build());
+        contract.claim_welcome_box();
+    }
+
+    #[test]
+    fn relayed_welcome_box_is_credited_to_user() {
+        let mut contract = setup_welcome(2);
+        let alice = account("alice.near");
+
+        claim_relayed(&mut contract, &alice, &relayer());
+
+        assert_eq!(contract.get_welcome_boxes(), (1, 1));
+        assert_eq!(contract.get_user_rewards(alice.clone()).2, 1);
+        assert_eq!(contract.get_user_rewards(relayer()).2, 0);
+        assert!(contract.get_achievements(alice).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOT_RELAYED]")]
+    fn direct_call_is_not_relayed() {
+        let mut contract = setup_welcome(1);
+
+        claim_relayed(&mut contract, &account("alice.near"), &account("alice.near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "[NOT_RELAYED]")]
+    fn unknown_relayer() {
+        let mut contract = setup_welcome(1);
+
+        claim_relayed(&mut contract, &account("alice.near"), &account("bob.near"));
+    }
+
+    #[test]
+    #[should_panic(expected = "[NO_WELCOME_BOXES]")]
+    fn welcome_boxes_run_out() {
+        let mut contract = setup_welcome(1);
+
+        claim_relayed(&mut contract, &account("alice.near"), &relayer());
+        claim_relayed(&mut contract, &account("bob.near"), &relayer());
+    }
+
+    #[test]
+    #[should_panic(expected = "[WELCOME_BOX_CLAIMED]")]
+    fn welcome_box_is_claimed_once() {
+        let mut contract = setup_welcome(2);
+
+        claim_relayed(&mut contract, &account("alice.near"), &relayer());
+        claim_relayed(&mut contract, &account("alice.near"), &relayer());
+    }
+
+    #[test]
+    #[should_panic(expected = "[WELCOME_BOX_CLAIMED]")]
+    fn welcome_box_after_paid_box() {
+        let mut contract = setup_welcome(1);
+        let alice = account("alice.near");
+        open_box(&mut contract, &alice, None);
+
+        claim_relayed(&mut contract, &alice, &relayer());
+    }
+
+    #[test]
+    fn welcome_registered_account_is_not_refunded() {
+        let mut contract = setup_welcome(1);
+        let alice = account("alice.near");
+        claim_relayed(&mut contract, &alice, &relayer());
+
+        testing_env!(context(&alice).attached_deposit(1).build());
+        assert!(contract.storage_unregister(Some(true)));
+
+        assert!(contract.storage_balance_of(alice.clone()).is_none());
+        assert!(get_created_receipts().iter().all(|receipt| receipt.receiver_id != alice));
+    }
+
+    #[test]
+    fn deposit_registered_account_is_refunded() {
+        let mut contract = setup_welcome(1);
+        let bob = account("bob.near");
+        let storage_deposit = contract.storage_balance_bounds().min.0;
+        testing_env!(context(&bob).attached_deposit(storage_deposit).build());
+        contract.storage_deposit(None, None);
+
+        testing_env!(context(&bob).attached_deposit(1).build());
+        assert!(contract.storage_unregister(None));
+
+        let refund = get_created_receipts().into_iter().find(|receipt| receipt.receiver_id == bob);
+        assert!(matches!(refund.unwrap().actions[..], [VmAction::Transfer { deposit }] if deposit == storage_deposit + 1));
+    }
+}
@@ -0,0 +1,116 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::IndexerError;
+
+// Subset of near-lake-framework StreamerMessage, one JSON file per block
+#[derive(Deserialize)]
+pub struct StreamerMessage {
+    pub block: Block,
+    pub shards: Vec<Shard>,
+}
+
+#[derive(Deserialize)]
+pub struct Block {
+    pub header: BlockHeader,
+}
+
+#[derive(Deserialize)]
+pub struct BlockHeader {
+    pub height: u64,
+    // Nanoseconds
+    pub timestamp: u64,
+}
+
+#[derive(Deserialize)]
+pub struct Shard {
+    #[serde(default)]
+    pub receipt_execution_outcomes: Vec<ExecutionOutcomeWithReceipt>,
+}
+
+#[derive(Deserialize)]
+pub struct ExecutionOutcomeWithReceipt {
+    pub execution_outcome: ExecutionOutcomeWithId,
+    pub receipt: Receipt,
+}
+
+#[derive(Deserialize)]
+pub struct ExecutionOutcomeWithId {
+    pub outcome: ExecutionOutcome,
+}
+
+#[derive(Deserialize)]
+pub struct ExecutionOutcome {
+    pub logs: Vec<String>,
+    pub executor_id: String,
+    // SuccessValue, SuccessReceiptId or Failure
+    pub status: Value,
+}
+
+#[derive(Deserialize)]
+pub struct Receipt {
+    pub receipt_id: String,
+    pub predecessor_id: String,
+}
+
+// Logs of one successful receipt executed by indexed contract
+pub struct ContractLogs {
+    pub block_height: u64,
+    pub block_timestamp: u64,
+    pub receipt_id: String,
+    pub predecessor_id: String,
+    pub logs: Vec<String>,
+}
+
+impl ExecutionOutcome {
+    // Logs of failed receipt are kept by the node, but contract state changes are reverted
+    pub fn is_success(&self) -> bool {
+        self.status.get("Failure").is_none()
+    }
+}
+
+impl StreamerMessage {
+    pub fn from_file(path: &Path) -> Result<Self, IndexerError> {
+        let data = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn contract_logs(&self, contract_id: &str) -> Vec<ContractLogs> {
+        self.shards
+            .iter()
+            .flat_map(|shard| shard.receipt_execution_outcomes.iter())
+            .filter(|item| {
+                let outcome = &item.execution_outcome.outcome;
+                outcome.executor_id == contract_id && outcome.is_success() && !outcome.logs.is_empty()
+            })
+            .map(|item| ContractLogs {
+                block_height: self.block.header.height,
+                block_timestamp: self.block.header.timestamp,
+                receipt_id: item.receipt.receipt_id.clone(),
+                predecessor_id: item.receipt.predecessor_id.clone(),
+                logs: item.execution_outcome.outcome.logs.clone(),
+            })
+            .collect()
+    }
+}
+
+// JSON files from given paths, directories are read recursively. Files are sorted by path,
+// so zero-padded block heights (near-lake bucket layout) are processed in order
+pub fn collect_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>, IndexerError> {
+    let mut files = vec![];
+    for path in paths {
+        if path.is_dir() {
+            let entries: Vec<PathBuf> = fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<_, _>>()?;
+            files.extend(collect_files(&entries)?);
+        } else if path.extension().and_then(|extension| extension.to_str()) == Some("json") {
+            files.push(path.clone());
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
@@ -0,0 +1,59 @@
+// Off-chain indexer for LOL MemeCoin contract. Reads near-lake StreamerMessage JSON dumps,
+// parses box rewards and NEP-141 events from contract logs and stores them into SQLite
+use std::path::Path;
+
+pub mod lake;
+pub mod parser;
+pub mod store;
+
+use crate::lake::StreamerMessage;
+use crate::parser::parse_log;
+use crate::store::Store;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IndexerError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Invalid log: {0}")]
+    InvalidLog(String),
+}
+
+pub struct Indexer {
+    contract_id: String,
+    store: Store,
+}
+
+impl Indexer {
+    pub fn new(contract_id: String, store: Store) -> Self {
+        Indexer { contract_id, store }
+    }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    // Index one block file, returns number of new events
+    pub fn index_file(&mut self, path: &Path) -> Result<usize, IndexerError> {
+        let message = StreamerMessage::from_file(path)?;
+        let mut inserted = 0;
+        for contract_logs in message.contract_logs(&self.contract_id) {
+            let events = contract_logs.logs
+                .iter()
+                .enumerate()
+                .map(|(log_index, log)| parse_log(log).map(|events| (log_index, events)))
+                .collect::<Result<Vec<_>, _>>()?;
+            inserted += self.store.insert_logs(&contract_logs, &events)?;
+        }
+
+        Ok(inserted)
+    }
+
+    // Update user stats, rankings and daily aggregates after files are indexed
+    pub fn finish(&mut self) -> Result<(), IndexerError> {
+        self.store.rebuild_aggregates()
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use std::process;
+
+use lol_indexer::lake::collect_files;
+use lol_indexer::store::Store;
+use lol_indexer::{Indexer, IndexerError};
+
+const USAGE: &str = "Usage: lol-indexer --contract <account_id> --db <sqlite_path> <block_json_file_or_dir>...";
+
+struct Args {
+    contract_id: String,
+    db_path: PathBuf,
+    paths: Vec<PathBuf>,
+}
+
+fn parse_args() -> Option<Args> {
+    let mut contract_id = None;
+    let mut db_path = None;
+    let mut paths = vec![];
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--contract" => contract_id = Some(args.next()?),
+            "--db" => db_path = Some(PathBuf::from(args.next()?)),
+            "-h" | "--help" => return None,
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        return None;
+    }
+
+    Some(Args { contract_id: contract_id?, db_path: db_path?, paths })
+}
+
+fn run(args: Args) -> Result<(), IndexerError> {
+    let files = collect_files(&args.paths)?;
+    let mut indexer = Indexer::new(args.contract_id, Store::open(&args.db_path)?);
+
+    let mut total = 0;
+    for file in &files {
+        total += indexer.index_file(file)?;
+    }
+    indexer.finish()?;
+
+    println!(
+        "Indexed {} files, {} new events, last block {}",
+        files.len(),
+        total,
+        indexer.store().last_block_height()?.map_or("-".to_string(), |height| height.to_string())
+    );
+    Ok(())
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|| {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    });
+
+    if let Err(error) = run(args) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,107 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::IndexerError;
+
+const REWARD_LOG_PREFIX: &str = "Reward: ";
+const DAILY_BOX_LOG_PREFIX: &str = "Daily box: ";
+const EVENT_LOG_PREFIX: &str = "EVENT_JSON:";
+
+#[derive(Debug, PartialEq)]
+pub enum ContractEvent {
+    // Box opened: reward type index (0 - basic box), LOL and NEAR rewards. Daily box is a basic box without NEAR reward
+    Reward { account_id: String, reward_type: u8, lol_amount: u128, near_amount: u128 },
+    FtMint { owner_id: String, amount: u128 },
+    FtBurn { owner_id: String, amount: u128 },
+    FtTransfer { old_owner_id: String, new_owner_id: String, amount: u128 },
+}
+
+// NEP-297 event, data is parsed only for indexed standards. NFT events of box NFTs and badges have no amount
+#[derive(Deserialize)]
+struct EventLog {
+    standard: String,
+    event: String,
+    data: Value,
+}
+
+#[derive(Deserialize)]
+struct EventData {
+    owner_id: Option<String>,
+    old_owner_id: Option<String>,
+    new_owner_id: Option<String>,
+    amount: String,
+}
+
+// Parse contract log, logs that are not indexed return empty list
+pub fn parse_log(log: &str) -> Result<Vec<ContractEvent>, IndexerError> {
+    if let Some(reward) = log.strip_prefix(REWARD_LOG_PREFIX) {
+        return parse_reward(reward).map(|event| vec![event]);
+    }
+    if let Some(daily_box) = log.strip_prefix(DAILY_BOX_LOG_PREFIX) {
+        return parse_daily_box(daily_box).map(|event| vec![event]);
+    }
+    if let Some(event) = log.strip_prefix(EVENT_LOG_PREFIX) {
+        return parse_ft_event(event);
+    }
+
+    Ok(vec![])
+}
+
+// Reward: {account_id}, {reward_type}, {lol_amount}, {near_amount}
+fn parse_reward(reward: &str) -> Result<ContractEvent, IndexerError> {
+    let invalid = || IndexerError::InvalidLog(format!("{}{}", REWARD_LOG_PREFIX, reward));
+    let parts: Vec<&str> = reward.split(", ").collect();
+    if parts.len() != 4 {
+        return Err(invalid());
+    }
+
+    Ok(ContractEvent::Reward {
+        account_id: parts[0].to_string(),
+        reward_type: parts[1].parse().map_err(|_| invalid())?,
+        lol_amount: parts[2].parse().map_err(|_| invalid())?,
+        near_amount: parts[3].parse().map_err(|_| invalid())?,
+    })
+}
+
+// Daily box: {account_id}, {lol_amount}, {daily_streak}
+fn parse_daily_box(daily_box: &str) -> Result<ContractEvent, IndexerError> {
+    let invalid = || IndexerError::InvalidLog(format!("{}{}", DAILY_BOX_LOG_PREFIX, daily_box));
+    let parts: Vec<&str> = daily_box.split(", ").collect();
+    if parts.len() != 3 {
+        return Err(invalid());
+    }
+    parts[2].parse::<u32>().map_err(|_| invalid())?;
+
+    Ok(ContractEvent::Reward {
+        account_id: parts[0].to_string(),
+        reward_type: 0,
+        lol_amount: parts[1].parse().map_err(|_| invalid())?,
+        near_amount: 0,
+    })
+}
+
+fn parse_ft_event(event: &str) -> Result<Vec<ContractEvent>, IndexerError> {
+    let event: EventLog = serde_json::from_str(event)?;
+    if event.standard != "nep141" || !["ft_mint", "ft_burn", "ft_transfer"].contains(&event.event.as_str()) {
+        return Ok(vec![]);
+    }
+
+    let data: Vec<EventData> = serde_json::from_value(event.data)?;
+    data
+        .into_iter()
+        .map(|data| {
+            let invalid = || IndexerError::InvalidLog(format!("{} {}", event.event, data.amount));
+            let amount: u128 = data.amount.parse().map_err(|_| invalid())?;
+            let event = match event.event.as_str() {
+                "ft_mint" => ContractEvent::FtMint { owner_id: data.owner_id.ok_or_else(invalid)?, amount },
+                "ft_burn" => ContractEvent::FtBurn { owner_id: data.owner_id.ok_or_else(invalid)?, amount },
+                _ => ContractEvent::FtTransfer {
+                    old_owner_id: data.old_owner_id.ok_or_else(invalid)?,
+                    new_owner_id: data.new_owner_id.ok_or_else(invalid)?,
+                    amount,
+                },
+            };
+            Ok(event)
+        })
+        .collect()
+}
@@ -0,0 +1,331 @@
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use crate::lake::ContractLogs;
+use crate::parser::ContractEvent;
+use crate::IndexerError;
+
+const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+// Amounts are u128 yocto values, SQLite integers are i64 so they are stored as decimal strings.
+// winning_boxes - boxes with NEAR prize (reward type > 0), logs don't show if box was allowed to be premium
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS rewards (
+    receipt_id TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    block_timestamp INTEGER NOT NULL,
+    account_id TEXT NOT NULL,
+    reward_type INTEGER NOT NULL,
+    lol_amount TEXT NOT NULL,
+    near_amount TEXT NOT NULL,
+    PRIMARY KEY (receipt_id, log_index)
+);
+CREATE INDEX IF NOT EXISTS rewards_account_id ON rewards (account_id, block_height);
+
+CREATE TABLE IF NOT EXISTS ft_events (
+    receipt_id TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    block_timestamp INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    from_account TEXT,
+    to_account TEXT,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (receipt_id, log_index, event_index)
+);
+CREATE INDEX IF NOT EXISTS ft_events_from_account ON ft_events (from_account, block_height);
+CREATE INDEX IF NOT EXISTS ft_events_to_account ON ft_events (to_account, block_height);
+
+CREATE TABLE IF NOT EXISTS user_stats (
+    account_id TEXT PRIMARY KEY,
+    boxes_opened INTEGER NOT NULL,
+    winning_boxes INTEGER NOT NULL,
+    lol_reward TEXT NOT NULL,
+    near_reward TEXT NOT NULL,
+    lol_rank INTEGER NOT NULL,
+    near_rank INTEGER NOT NULL,
+    first_box_at INTEGER NOT NULL,
+    last_box_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS daily_stats (
+    day TEXT PRIMARY KEY,
+    boxes_opened INTEGER NOT NULL,
+    winning_boxes INTEGER NOT NULL,
+    unique_users INTEGER NOT NULL,
+    lol_rewarded TEXT NOT NULL,
+    near_rewarded TEXT NOT NULL,
+    lol_minted TEXT NOT NULL,
+    lol_burned TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS indexer_state (
+    key TEXT PRIMARY KEY,
+    value INTEGER NOT NULL
+);
+";
+
+#[derive(Default)]
+struct UserStats {
+    boxes_opened: u64,
+    winning_boxes: u64,
+    lol_reward: u128,
+    near_reward: u128,
+    first_box_at: u64,
+    last_box_at: u64,
+}
+
+#[derive(Default)]
+struct DailyStats {
+    boxes_opened: u64,
+    winning_boxes: u64,
+    users: HashSet<String>,
+    lol_rewarded: u128,
+    near_rewarded: u128,
+    lol_minted: u128,
+    lol_burned: u128,
+}
+
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self, IndexerError> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Store { connection })
+    }
+
+    pub fn open_in_memory() -> Result<Self, IndexerError> {
+        let connection = Connection::open_in_memory()?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Store { connection })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    // Save events from receipt logs, already indexed receipts are skipped. Returns number of saved events
+    pub fn insert_logs(&mut self, contract_logs: &ContractLogs, events: &[(usize, Vec<ContractEvent>)]) -> Result<usize, IndexerError> {
+        let transaction = self.connection.transaction()?;
+        let mut inserted = 0;
+        for (log_index, log_events) in events {
+            for (event_index, event) in log_events.iter().enumerate() {
+                inserted += match event {
+                    ContractEvent::Reward { account_id, reward_type, lol_amount, near_amount } => transaction.execute(
+                        "INSERT OR IGNORE INTO rewards
+                            (receipt_id, log_index, block_height, block_timestamp, account_id, reward_type, lol_amount, near_amount)
+                            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                        params![
+                            contract_logs.receipt_id,
+                            *log_index as i64,
+                            contract_logs.block_height as i64,
+                            contract_logs.block_timestamp as i64,
+                            account_id,
+                            *reward_type as i64,
+                            lol_amount.to_string(),
+                            near_amount.to_string(),
+                        ],
+                    )?,
+                    ContractEvent::FtMint { owner_id, amount } => {
+                        insert_ft_event(&transaction, contract_logs, *log_index, event_index, "ft_mint", None, Some(owner_id), *amount)?
+                    }
+                    ContractEvent::FtBurn { owner_id, amount } => {
+                        insert_ft_event(&transaction, contract_logs, *log_index, event_index, "ft_burn", Some(owner_id), None, *amount)?
+                    }
+                    ContractEvent::FtTransfer { old_owner_id, new_owner_id, amount } => insert_ft_event(
+                        &transaction,
+                        contract_logs,
+                        *log_index,
+                        event_index,
+                        "ft_transfer",
+                        Some(old_owner_id),
+                        Some(new_owner_id),
+                        *amount,
+                    )?,
+                };
+            }
+        }
+        transaction.execute(
+            "INSERT INTO indexer_state (key, value) VALUES ('last_block_height', ?1)
+                ON CONFLICT (key) DO UPDATE SET value = MAX(value, excluded.value)",
+            params![contract_logs.block_height as i64],
+        )?;
+        transaction.commit()?;
+
+        Ok(inserted)
+    }
+
+    pub fn last_block_height(&self) -> Result<Option<u64>, IndexerError> {
+        let height: Option<i64> = self.connection
+            .query_row("SELECT value FROM indexer_state WHERE key = 'last_block_height'", [], |row| row.get(0))
+            .optional()?;
+        Ok(height.map(|height| height as u64))
+    }
+
+    // Rebuild user totals, full rankings and daily aggregates from indexed events
+    pub fn rebuild_aggregates(&mut self) -> Result<(), IndexerError> {
+        let mut users: HashMap<String, UserStats> = HashMap::new();
+        let mut days: BTreeMap<u64, DailyStats> = BTreeMap::new();
+
+        {
+            let mut statement = self.connection.prepare(
+                "SELECT account_id, reward_type, lol_amount, near_amount, block_timestamp FROM rewards
+                    ORDER BY block_height, receipt_id, log_index",
+            )?;
+            let mut rows = statement.query([])?;
+            while let Some(row) = rows.next()? {
+                let account_id: String = row.get(0)?;
+                let reward_type: i64 = row.get(1)?;
+                let lol_amount = parse_amount(&row.get::<_, String>(2)?)?;
+                let near_amount = parse_amount(&row.get::<_, String>(3)?)?;
+                let timestamp = row.get::<_, i64>(4)? as u64;
+
+                let user = users.entry(account_id.clone()).or_default();
+                if user.boxes_opened == 0 {
+                    user.first_box_at = timestamp;
+                }
+                user.boxes_opened += 1;
+                user.winning_boxes += (reward_type != 0) as u64;
+                user.lol_reward += lol_amount;
+                user.near_reward += near_amount;
+                user.last_box_at = timestamp;
+
+                let day = days.entry(timestamp / NANOS_PER_DAY).or_default();
+                day.boxes_opened += 1;
+                day.winning_boxes += (reward_type != 0) as u64;
+                day.users.insert(account_id);
+                day.lol_rewarded += lol_amount;
+                day.near_rewarded += near_amount;
+            }
+        }
+
+        {
+            let mut statement = self.connection.prepare(
+                "SELECT kind, amount, block_timestamp FROM ft_events WHERE kind IN ('ft_mint', 'ft_burn')",
+            )?;
+            let mut rows = statement.query([])?;
+            while let Some(row) = rows.next()? {
+                let kind: String = row.get(0)?;
+                let amount = parse_amount(&row.get::<_, String>(1)?)?;
+                let timestamp = row.get::<_, i64>(2)? as u64;
+
+                let day = days.entry(timestamp / NANOS_PER_DAY).or_default();
+                if kind == "ft_mint" {
+                    day.lol_minted += amount;
+                } else {
+                    day.lol_burned += amount;
+                }
+            }
+        }
+
+        let lol_ranks = ranks(&users, |user| user.lol_reward);
+        let near_ranks = ranks(&users, |user| user.near_reward);
+
+        let transaction = self.connection.transaction()?;
+        transaction.execute("DELETE FROM user_stats", [])?;
+        transaction.execute("DELETE FROM daily_stats", [])?;
+        for (account_id, user) in &users {
+            transaction.execute(
+                "INSERT INTO user_stats
+                    (account_id, boxes_opened, winning_boxes, lol_reward, near_reward, lol_rank, near_rank, first_box_at, last_box_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    account_id,
+                    user.boxes_opened as i64,
+                    user.winning_boxes as i64,
+                    user.lol_reward.to_string(),
+                    user.near_reward.to_string(),
+                    lol_ranks[account_id] as i64,
+                    near_ranks[account_id] as i64,
+                    user.first_box_at as i64,
+                    user.last_box_at as i64,
+                ],
+            )?;
+        }
+        for (day, stats) in &days {
+            transaction.execute(
+                "INSERT INTO daily_stats
+                    (day, boxes_opened, winning_boxes, unique_users, lol_rewarded, near_rewarded, lol_minted, lol_burned)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    format_day(*day),
+                    stats.boxes_opened as i64,
+                    stats.winning_boxes as i64,
+                    stats.users.len() as i64,
+                    stats.lol_rewarded.to_string(),
+                    stats.near_rewarded.to_string(),
+                    stats.lol_minted.to_string(),
+                    stats.lol_burned.to_string(),
+                ],
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_ft_event(
+    transaction: &Transaction,
+    contract_logs: &ContractLogs,
+    log_index: usize,
+    event_index: usize,
+    kind: &str,
+    from_account: Option<&String>,
+    to_account: Option<&String>,
+    amount: u128,
+) -> Result<usize, IndexerError> {
+    Ok(transaction.execute(
+        "INSERT OR IGNORE INTO ft_events
+            (receipt_id, log_index, event_index, block_height, block_timestamp, kind, from_account, to_account, amount)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            contract_logs.receipt_id,
+            log_index as i64,
+            event_index as i64,
+            contract_logs.block_height as i64,
+            contract_logs.block_timestamp as i64,
+            kind,
+            from_account,
+            to_account,
+            amount.to_string(),
+        ],
+    )?)
+}
+
+fn parse_amount(amount: &str) -> Result<u128, IndexerError> {
+    amount.parse().map_err(|_| IndexerError::InvalidLog(format!("Wrong amount {}", amount)))
+}
+
+// Rank 1 is the biggest amount, ties are ordered by account id
+fn ranks(users: &HashMap<String, UserStats>, amount: fn(&UserStats) -> u128) -> HashMap<String, usize> {
+    let mut sorted: Vec<(&String, u128)> = users.iter().map(|(account_id, user)| (account_id, amount(user))).collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    sorted
+        .into_iter()
+        .enumerate()
+        .map(|(index, (account_id, _))| (account_id.clone(), index + 1))
+        .collect()
+}
+
+// Days since unix epoch to YYYY-MM-DD, civil_from_days by Howard Hinnant
+fn format_day(days: u64) -> String {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use lol_indexer::lake::collect_files;
+use lol_indexer::parser::{parse_log, ContractEvent};
+use lol_indexer::store::Store;
+use lol_indexer::Indexer;
+
+const CONTRACT_ID: &str = "lol.near";
+const ONE_LOL: u128 = 1_000_000_000_000_000_000_000_000;
+
+// day, boxes_opened, winning_boxes, unique_users, lol_rewarded, near_rewarded, lol_minted, lol_burned
+type DailyRow = (String, i64, i64, i64, String, String, String, String);
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+}
+
+fn indexed_fixtures() -> Indexer {
+    let mut indexer = Indexer::new(CONTRACT_ID.to_string(), Store::open_in_memory().unwrap());
+    for file in collect_files(&[fixtures_dir()]).unwrap() {
+        indexer.index_file(&file).unwrap();
+    }
+    indexer.finish().unwrap();
+    indexer
+}
+
+#[test]
+fn parse_reward_log() {
+    assert_eq!(
+        parse_log("Reward: bob.near, 2, 1000, 10").unwrap(),
+        vec![ContractEvent::Reward { account_id: "bob.near".to_string(), reward_type: 2, lol_amount: 1000, near_amount: 10 }]
+    );
+    assert!(parse_log("Reward: bob.near, 2").is_err());
+}
+
+#[test]
+fn parse_daily_box_log_as_basic_box_reward() {
+    assert_eq!(
+        parse_log("Daily box: bob.near, 100, 1").unwrap(),
+        vec![ContractEvent::Reward { account_id: "bob.near".to_string(), reward_type: 0, lol_amount: 100, near_amount: 0 }]
+    );
+    assert!(parse_log("Daily box: bob.near, 100").is_err());
+}
+
+#[test]
+fn skip_nft_and_unknown_logs() {
+    let nft_mint = r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_mint","data":[{"owner_id":"bob.near","token_ids":["1"]}]}"#;
+    assert_eq!(parse_log(nft_mint).unwrap(), vec![]);
+    assert_eq!(parse_log("Boxes gifted: bob.near, alice.near, 1").unwrap(), vec![]);
+}
+
+#[test]
+fn collect_fixture_files_in_block_order() {
+    let files = collect_files(&[fixtures_dir()]).unwrap();
+    let names: Vec<&str> = files.iter().map(|file| file.file_name().unwrap().to_str().unwrap()).collect();
+    assert_eq!(names, vec!["000000100.json", "000000101.json", "000000200.json", "000000300.json"]);
+}
+
+#[test]
+fn index_rewards_from_successful_contract_receipts() {
+    let indexer = indexed_fixtures();
+    let connection = indexer.store().connection();
+
+    let mut statement = connection
+        .prepare("SELECT account_id, reward_type, lol_amount, near_amount, block_height FROM rewards ORDER BY block_height")
+        .unwrap();
+    let rewards: Vec<(String, i64, String, String, i64)> = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    // Failed receipt of eve.near and receipt of other contract are skipped
+    assert_eq!(
+        rewards,
+        vec![
+            ("bob.near".to_string(), 2, (1000 * ONE_LOL).to_string(), ONE_LOL.to_string(), 100),
+            ("alice.near".to_string(), 0, (5000 * ONE_LOL).to_string(), "0".to_string(), 101),
+            ("bob.near".to_string(), 0, (2000 * ONE_LOL).to_string(), "0".to_string(), 200),
+            ("alice.near".to_string(), 0, (500 * ONE_LOL).to_string(), "0".to_string(), 300),
+        ]
+    );
+    assert_eq!(indexer.store().last_block_height().unwrap(), Some(300));
+}
+
+#[test]
+fn index_ft_events() {
+    let indexer = indexed_fixtures();
+    let connection = indexer.store().connection();
+
+    let mut statement = connection
+        .prepare("SELECT kind, from_account, to_account, amount FROM ft_events ORDER BY block_height, log_index")
+        .unwrap();
+    let events: Vec<(String, Option<String>, Option<String>, String)> = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(
+        events,
+        vec![
+            ("ft_transfer".to_string(), Some("lol.near".to_string()), Some("bob.near".to_string()), (1000 * ONE_LOL).to_string()),
+            ("ft_mint".to_string(), None, Some("lol.near".to_string()), (7000 * ONE_LOL).to_string()),
+            ("ft_burn".to_string(), Some("alice.near".to_string()), None, (2000 * ONE_LOL).to_string()),
+        ]
+    );
+}
+
+#[test]
+fn rebuild_user_stats_with_rankings() {
+    let indexer = indexed_fixtures();
+    let connection = indexer.store().connection();
+
+    let mut statement = connection
+        .prepare("SELECT account_id, boxes_opened, winning_boxes, lol_reward, near_reward, lol_rank, near_rank FROM user_stats ORDER BY lol_rank")
+        .unwrap();
+    let users: Vec<(String, i64, i64, String, String, i64, i64)> = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(
+        users,
+        vec![
+            ("alice.near".to_string(), 2, 0, (5500 * ONE_LOL).to_string(), "0".to_string(), 1, 2),
+            ("bob.near".to_string(), 2, 1, (3000 * ONE_LOL).to_string(), ONE_LOL.to_string(), 2, 1),
+        ]
+    );
+}
+
+#[test]
+fn rebuild_daily_stats() {
+    let indexer = indexed_fixtures();
+    let connection = indexer.store().connection();
+
+    let mut statement = connection
+        .prepare("SELECT day, boxes_opened, winning_boxes, unique_users, lol_rewarded, near_rewarded, lol_minted, lol_burned FROM daily_stats ORDER BY day")
+        .unwrap();
+    let days: Vec<DailyRow> = statement
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(
+        days,
+        vec![
+            (
+                "2024-01-06".to_string(),
+                2,
+                1,
+                2,
+                (6000 * ONE_LOL).to_string(),
+                ONE_LOL.to_string(),
+                (7000 * ONE_LOL).to_string(),
+                (2000 * ONE_LOL).to_string(),
+            ),
+            ("2024-01-07".to_string(), 2, 0, 2, (2500 * ONE_LOL).to_string(), "0".to_string(), "0".to_string(), "0".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn reindex_is_idempotent() {
+    let mut indexer = indexed_fixtures();
+    let files = collect_files(&[fixtures_dir()]).unwrap();
+    let inserted: usize = files.iter().map(|file| indexer.index_file(file).unwrap()).sum();
+    indexer.finish().unwrap();
+
+    assert_eq!(inserted, 0);
+    let count: i64 = indexer.store().connection().query_row("SELECT COUNT(*) FROM rewards", [], |row| row.get(0)).unwrap();
+    assert_eq!(count, 4);
+}
@@ -0,0 +1,36 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupSet;
+use near_sdk::{env, near_bindgen, AccountId, PanicOnDefault};
+
+// Stand-in for verifier contract of VerificationMode::Oracle, owner marks verified accounts by hand
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct MockVerifier {
+    owner_id: AccountId,
+    verified: LookupSet<AccountId>,
+}
+
+#[near_bindgen]
+impl MockVerifier {
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        Self {
+            owner_id,
+            verified: LookupSet::new(b"v"),
+        }
+    }
+
+    pub fn is_verified(&self, account_id: AccountId) -> bool {
+        self.verified.contains(&account_id)
+    }
+
+    pub fn set_verified(&mut self, account_id: AccountId, is_verified: bool) {
+        assert_eq!(env::predecessor_account_id(), self.owner_id, "Unauthorized");
+
+        if is_verified {
+            self.verified.insert(&account_id);
+        } else {
+            self.verified.remove(&account_id);
+        }
+    }
+}
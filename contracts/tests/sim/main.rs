@@ -0,0 +1,135 @@
+#![cfg(feature = "sandbox")]
+
+// Welcome box claimed with delegate action (NEP-366) in near-sandbox, run `./build.sh` first
+use near_jsonrpc_client::{methods, JsonRpcClient};
+use near_primitives::delegate_action::{DelegateAction, NonDelegateAction, SignedDelegateAction};
+use near_primitives::hash::CryptoHash;
+use near_primitives::signable_message::{SignableMessage, SignableMessageType};
+use near_primitives::transaction::{Action, FunctionCallAction, SignedTransaction, Transaction};
+use near_primitives::views::FinalExecutionStatus;
+use near_workspaces::network::Sandbox;
+use near_workspaces::{Account, Contract, Worker};
+use sandbox_crypto::{InMemorySigner, Signer};
+use serde_json::json;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const COIN_WASM: &str = "../out/coin.wasm";
+const GAS: u64 = 100_000_000_000_000;
+
+async fn setup() -> Result<(Worker<Sandbox>, Contract, Account)> {
+    let worker = near_workspaces::sandbox().await?;
+    let contract = worker.dev_deploy(&std::fs::read(COIN_WASM)?).await?;
+    contract.call("new_default_meta").args_json(json!({ "owner_id": contract.id() })).transact().await?.into_result()?;
+
+    let relayer = worker.dev_create_account().await?;
+    contract.call("set_welcome_boxes").args_json(json!({ "count": 10 })).transact().await?.into_result()?;
+    contract
+        .call("add_welcome_relayer")
+        .args_json(json!({ "account_id": relayer.id() }))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok((worker, contract, relayer))
+}
+
+fn signer(account: &Account) -> Result<InMemorySigner> {
+    Ok(InMemorySigner::from_secret_key(account.id().clone(), account.secret_key().to_string().parse()?))
+}
+
+// Access key nonce of the account and current block for the next action signed by it
+async fn next_nonce(worker: &Worker<Sandbox>, account: &Account) -> Result<(u64, u64, CryptoHash)> {
+    let access_key = account.view_access_key(&account.secret_key().public_key()).await?;
+    let block = worker.view_block().await?;
+    Ok((access_key.nonce + 1, block.height(), CryptoHash(block.hash().0)))
+}
+
+// User signs claim_welcome_box call, relayer submits it and pays for gas
+async fn claim_relayed(worker: &Worker<Sandbox>, contract: &Contract, user: &Account, relayer: &Account) -> Result<FinalExecutionStatus> {
+    let user_signer = signer(user)?;
+    let (nonce, height, _) = next_nonce(worker, user).await?;
+    let claim = Action::FunctionCall(FunctionCallAction {
+        method_name: "claim_welcome_box".to_string(),
+        args: b"{}".to_vec(),
+        gas: GAS,
+        deposit: 0,
+    });
+    let delegate_action = DelegateAction {
+        sender_id: user.id().clone(),
+        receiver_id: contract.id().clone(),
+        actions: vec![NonDelegateAction::try_from(claim).unwrap()],
+        nonce,
+        max_block_height: height + 100,
+        public_key: user_signer.public_key(),
+    };
+    let signature = SignableMessage::new(&delegate_action, SignableMessageType::DelegateAction).sign(&user_signer);
+
+    let relayer_signer = signer(relayer)?;
+    let (nonce, _, block_hash) = next_nonce(worker, relayer).await?;
+    let transaction = Transaction {
+        signer_id: relayer.id().clone(),
+        public_key: relayer_signer.public_key(),
+        nonce,
+        receiver_id: user.id().clone(),
+        block_hash,
+        actions: vec![Action::Delegate(SignedDelegateAction { delegate_action, signature })],
+    };
+    let (hash, _) = transaction.get_hash_and_size();
+    let signed_transaction = SignedTransaction::new(relayer_signer.sign(hash.as_ref()), transaction);
+
+    let client = JsonRpcClient::connect(worker.rpc_addr());
+    let outcome = client.call(methods::broadcast_tx_commit::RpcBroadcastTxCommitRequest { signed_transaction }).await?;
+    // Delegate action is a separate receipt, its failure doesn't fail the transaction
+    for receipt in outcome.receipts_outcome {
+        if let near_primitives::views::ExecutionStatusView::Failure(error) = receipt.outcome.status {
+            return Ok(FinalExecutionStatus::Failure(error));
+        }
+    }
+    Ok(outcome.status)
+}
+
+async fn boxes_opened(contract: &Contract, account: &Account) -> Result<u64> {
+    let rewards: serde_json::Value =
+        contract.view("get_user_rewards").args_json(json!({ "account_id": account.id() })).await?.json()?;
+    Ok(rewards[2].as_u64().unwrap())
+}
+
+#[tokio::test]
+async fn relayed_welcome_box_is_credited_to_user() -> Result<()> {
+    let (worker, contract, relayer) = setup().await?;
+    let user = worker.dev_create_account().await?;
+
+    let status = claim_relayed(&worker, &contract, &user, &relayer).await?;
+
+    assert!(matches!(status, FinalExecutionStatus::SuccessValue(_)), "{:?}", status);
+    assert_eq!(boxes_opened(&contract, &user).await?, 1);
+    assert_eq!(boxes_opened(&contract, &relayer).await?, 0);
+    let welcome_boxes: (u32, u32) = contract.view("get_welcome_boxes").await?.json()?;
+    assert_eq!(welcome_boxes, (9, 1));
+    Ok(())
+}
+
+#[tokio::test]
+async fn welcome_box_is_not_relayed_by_other_account() -> Result<()> {
+    let (worker, contract, _) = setup().await?;
+    let user = worker.dev_create_account().await?;
+    let other = worker.dev_create_account().await?;
+
+    let status = claim_relayed(&worker, &contract, &user, &other).await?;
+
+    assert!(format!("{:?}", status).contains("NOT_RELAYED"), "{:?}", status);
+    assert_eq!(boxes_opened(&contract, &user).await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn direct_welcome_box_call_fails() -> Result<()> {
+    let (worker, contract, _) = setup().await?;
+    let user = worker.dev_create_account().await?;
+
+    let result = user.call(contract.id(), "claim_welcome_box").gas(near_workspaces::types::Gas::from_gas(GAS)).transact().await?;
+
+    assert!(format!("{:?}", result.into_result()).contains("NOT_RELAYED"));
+    assert_eq!(boxes_opened(&contract, &user).await?, 0);
+    Ok(())
+}